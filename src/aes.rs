@@ -0,0 +1,409 @@
+//! AES coprocessor driver (ECB, CBC, CTR and GCM with 128 or 256-bit keys).
+//!
+//! Blocks go through `AES_DINR` and `AES_DOUTR` in byte-swap mode
+//! (`DATATYPE` = 0b10), so buffers keep the usual byte order. The key and IV
+//! registers are not swapped: the most significant word of the key goes to
+//! the highest `AES_KEYRx` and the first IV word to `AES_IVR3`.
+//!
+//! GCM runs through the four phases of the peripheral (init, header, payload
+//! and final) in one call; see [`Aes::gcm_encrypt`].
+//!
+//! The ciphers in [`Aes128`], [`Aes256`], [`Aes128Gcm`] and [`Aes256Gcm`]
+//! implement the RustCrypto `cipher` and `aead` traits on top of the driver.
+
+use stm32wba::stm32wba55;
+use cortex_m::{asm, peripheral::NVIC};
+use embassy_sync::waitqueue::AtomicWaker;
+use zeroize::Zeroize;
+
+use crate::{
+    completion::{block_until, wait_until},
+    Completion,
+};
+
+mod aead;
+mod cipher;
+
+pub use self::aead::{Aes128Gcm, Aes256Gcm};
+pub use self::cipher::{Aes128, Aes256};
+
+/// AES block size in bytes.
+pub const BLOCK_LEN: usize = 16;
+/// GCM nonce size in bytes.
+pub const NONCE_LEN: usize = 12;
+/// GCM tag size in bytes.
+pub const TAG_LEN: usize = 16;
+
+static WAKER: AtomicWaker = AtomicWaker::new();
+
+/// Errors from the AES driver.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// The key is neither 16 nor 32 bytes long.
+    KeyLength,
+    /// Input and output differ in length, or are not whole blocks.
+    DataLength,
+    /// The GCM tag does not match the data.
+    Tag,
+}
+
+/// Chaining mode, with its initialization vector.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Chaining {
+    Ecb,
+    Cbc([u8; BLOCK_LEN]),
+    /// Counter mode; the last word of the block is the counter.
+    Ctr([u8; BLOCK_LEN]),
+}
+
+impl Chaining {
+    const fn bits(&self) -> u8 {
+        match self {
+            Chaining::Ecb => 0b00,
+            Chaining::Cbc(_) => 0b01,
+            Chaining::Ctr(_) => 0b10,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+// AES_CR.MODE values
+const MODE_ENCRYPT: u8 = 0b00;
+const MODE_KEY_DERIVATION: u8 = 0b01;
+const MODE_DECRYPT: u8 = 0b10;
+
+// AES_CR.CHMOD value of GCM; CHMOD[2] stays clear
+const CHMOD_GCM: u8 = 0b11;
+
+// AES_CR.GCMPH values
+const GCM_INIT: u8 = 0b00;
+const GCM_HEADER: u8 = 0b01;
+const GCM_PAYLOAD: u8 = 0b10;
+const GCM_FINAL: u8 = 0b11;
+
+/// Body of the `AES` interrupt handler, for [`Completion::Interrupt`].
+///
+/// Masks the computation complete interrupt and wakes the waiting task.
+pub fn on_interrupt() {
+    let aes = unsafe { &*stm32wba55::AES::ptr() };
+    aes.aes_ier().modify(|_, w| w.ccfie().clear_bit());
+    WAKER.wake();
+}
+
+/// AES driver.
+#[derive(Debug)]
+pub struct Aes {
+    aes: stm32wba55::AES,
+    completion: Completion,
+}
+
+impl Aes {
+    pub fn new(aes: stm32wba55::AES, rcc: &stm32wba55::RCC) -> Self {
+        // Enable AES peripheral clock on AHB2
+        rcc.rcc_ahb2enr().modify(|_, w| w.aesen().set_bit());
+        while rcc.rcc_ahb2enr().read().aesen().bit_is_clear() {
+            asm::nop();
+        }
+
+        Self {
+            aes,
+            completion: Completion::Polling,
+        }
+    }
+
+    /// Waits on the AES interrupt instead of spinning.
+    ///
+    /// Unmasks the AES interrupt in the NVIC; the application must call
+    /// [`on_interrupt`] from its `AES` handler.
+    pub fn enable_interrupt(&mut self) {
+        NVIC::unpend(stm32wba55::Interrupt::AES);
+        self.completion = Completion::Interrupt;
+        unsafe { NVIC::unmask(stm32wba55::Interrupt::AES) };
+    }
+
+    /// Masks the AES interrupt and goes back to polling.
+    pub fn disable_interrupt(&mut self) {
+        NVIC::mask(stm32wba55::Interrupt::AES);
+        self.completion = Completion::Polling;
+    }
+
+    /// Loads `key` and the IV of `chaining`, and enables the peripheral.
+    ///
+    /// ECB and CBC decryption first run the key derivation. CTR always
+    /// encrypts the counter, so `direction` does not matter there.
+    pub fn start(
+        &mut self,
+        key: &[u8],
+        chaining: Chaining,
+        direction: Direction,
+    ) -> Result<(), Error> {
+        if key.len() != 16 && key.len() != 32 {
+            return Err(Error::KeyLength);
+        }
+        let decrypt = direction == Direction::Decrypt && !matches!(chaining, Chaining::Ctr(_));
+
+        self.aes.aes_cr().modify(|_, w| w.en().clear_bit());
+        self.aes.aes_cr().modify(|_, w| unsafe {
+            w.mode().bits(if decrypt { MODE_KEY_DERIVATION } else { MODE_ENCRYPT });
+            w.chmod().bits(chaining.bits());
+            w.datatype().bits(0b10);
+            w.keysize().bit(key.len() == 32)
+        });
+        self.write_key(key);
+
+        if decrypt {
+            // Key derivation, then decryption with the derived key
+            self.aes.aes_cr().modify(|_, w| w.en().set_bit());
+            while !self.is_complete() {
+                asm::nop();
+            }
+            self.clear_complete();
+            self.aes.aes_cr().modify(|_, w| unsafe {
+                w.en().clear_bit();
+                w.mode().bits(MODE_DECRYPT)
+            });
+        }
+
+        if let Chaining::Cbc(iv) | Chaining::Ctr(iv) = chaining {
+            self.write_iv(&iv);
+        }
+        self.aes.aes_cr().modify(|_, w| w.en().set_bit());
+        Ok(())
+    }
+
+    /// Disables the peripheral.
+    pub fn stop(&mut self) {
+        self.aes.aes_cr().modify(|_, w| w.en().clear_bit());
+    }
+
+    /// Processes one block.
+    pub fn process_block(&mut self, input: &[u8; BLOCK_LEN], output: &mut [u8; BLOCK_LEN]) {
+        self.write_block(input);
+        block_until(self.completion, || self.is_complete(), || self.listen());
+        self.read_block(output);
+    }
+
+    /// Processes whole blocks from `input` into `output`.
+    pub fn process(&mut self, input: &[u8], output: &mut [u8]) -> Result<(), Error> {
+        check_lengths(input, output)?;
+        for (input, output) in input
+            .chunks_exact(BLOCK_LEN)
+            .zip(output.chunks_exact_mut(BLOCK_LEN))
+        {
+            self.process_block(input.try_into().unwrap(), output.try_into().unwrap());
+        }
+        Ok(())
+    }
+
+    /// Async counterpart of [`Aes::process_block`].
+    pub async fn process_block_async(
+        &mut self,
+        input: &[u8; BLOCK_LEN],
+        output: &mut [u8; BLOCK_LEN],
+    ) {
+        self.write_block(input);
+        wait_until(self.completion, &WAKER, || self.is_complete(), || self.listen()).await;
+        self.read_block(output);
+    }
+
+    /// Async counterpart of [`Aes::process`].
+    pub async fn process_async(&mut self, input: &[u8], output: &mut [u8]) -> Result<(), Error> {
+        check_lengths(input, output)?;
+        for (input, output) in input
+            .chunks_exact(BLOCK_LEN)
+            .zip(output.chunks_exact_mut(BLOCK_LEN))
+        {
+            self.process_block_async(input.try_into().unwrap(), output.try_into().unwrap())
+                .await;
+        }
+        Ok(())
+    }
+
+    /// Encrypts `buffer` in place with AES-GCM and returns the tag over `aad`
+    /// and the ciphertext.
+    pub fn gcm_encrypt(
+        &mut self,
+        key: &[u8],
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<[u8; TAG_LEN], Error> {
+        self.gcm(key, nonce, aad, buffer, Direction::Encrypt)
+    }
+
+    /// Decrypts `buffer` in place with AES-GCM and checks `tag`.
+    ///
+    /// The peripheral decrypts while it computes the tag, so on
+    /// [`Error::Tag`] the unauthenticated plaintext is wiped from `buffer`.
+    pub fn gcm_decrypt(
+        &mut self,
+        key: &[u8],
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &mut [u8],
+        tag: &[u8; TAG_LEN],
+    ) -> Result<(), Error> {
+        let computed = self.gcm(key, nonce, aad, buffer, Direction::Decrypt)?;
+        // Constant time: every byte is compared
+        let diff = computed.iter().zip(tag).fold(0, |diff, (a, b)| diff | (a ^ b));
+        if diff != 0 {
+            buffer.zeroize();
+            return Err(Error::Tag);
+        }
+        Ok(())
+    }
+
+    fn gcm(
+        &mut self,
+        key: &[u8],
+        nonce: &[u8; NONCE_LEN],
+        aad: &[u8],
+        buffer: &mut [u8],
+        direction: Direction,
+    ) -> Result<[u8; TAG_LEN], Error> {
+        if key.len() != 16 && key.len() != 32 {
+            return Err(Error::KeyLength);
+        }
+        let encrypt = direction == Direction::Encrypt;
+
+        // Init phase: the peripheral computes the hash key, then clears EN
+        self.aes.aes_cr().modify(|_, w| w.en().clear_bit());
+        self.aes.aes_cr().modify(|_, w| unsafe {
+            w.mode().bits(if encrypt { MODE_ENCRYPT } else { MODE_DECRYPT });
+            w.chmod().bits(CHMOD_GCM);
+            w.gcmph().bits(GCM_INIT);
+            w.npblb().bits(0);
+            w.datatype().bits(0b10);
+            w.keysize().bit(key.len() == 32)
+        });
+        self.write_key(key);
+        // The payload counter starts at 2, after the one of the tag
+        let mut iv = [0; BLOCK_LEN];
+        iv[..NONCE_LEN].copy_from_slice(nonce);
+        iv[BLOCK_LEN - 1] = 2;
+        self.write_iv(&iv);
+        self.aes.aes_cr().modify(|_, w| w.en().set_bit());
+        while !self.is_complete() {
+            asm::nop();
+        }
+        self.clear_complete();
+
+        self.aes.aes_cr().modify(|_, w| unsafe {
+            w.gcmph().bits(GCM_HEADER);
+            w.en().set_bit()
+        });
+        for chunk in aad.chunks(BLOCK_LEN) {
+            self.write_block(&padded(chunk));
+            block_until(self.completion, || self.is_complete(), || self.listen());
+            self.clear_complete();
+        }
+
+        self.aes.aes_cr().modify(|_, w| unsafe { w.gcmph().bits(GCM_PAYLOAD) });
+        for chunk in buffer.chunks_mut(BLOCK_LEN) {
+            if encrypt && chunk.len() < BLOCK_LEN {
+                // Keeps the encrypted padding out of the tag
+                let padding = (BLOCK_LEN - chunk.len()) as u8;
+                self.aes.aes_cr().modify(|_, w| unsafe { w.npblb().bits(padding) });
+            }
+            let mut output = [0; BLOCK_LEN];
+            self.process_block(&padded(chunk), &mut output);
+            chunk.copy_from_slice(&output[..chunk.len()]);
+        }
+
+        // Final phase: the bit lengths of the AAD and the payload give the tag
+        self.aes.aes_cr().modify(|_, w| unsafe { w.gcmph().bits(GCM_FINAL) });
+        let mut lengths = [0; BLOCK_LEN];
+        lengths[..8].copy_from_slice(&(aad.len() as u64 * 8).to_be_bytes());
+        lengths[8..].copy_from_slice(&(buffer.len() as u64 * 8).to_be_bytes());
+        let mut tag = [0; TAG_LEN];
+        self.process_block(&lengths, &mut tag);
+        self.stop();
+        Ok(tag)
+    }
+
+    fn write_key(&mut self, key: &[u8]) {
+        // The first key word is the most significant one
+        let words = key.len() / 4;
+        for (idx, chunk) in key.chunks_exact(4).enumerate() {
+            let word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            let keyr = words - 1 - idx;
+            unsafe {
+                match keyr {
+                    0 => self.aes.aes_keyr0().write(|w| w.bits(word)),
+                    1 => self.aes.aes_keyr1().write(|w| w.bits(word)),
+                    2 => self.aes.aes_keyr2().write(|w| w.bits(word)),
+                    3 => self.aes.aes_keyr3().write(|w| w.bits(word)),
+                    4 => self.aes.aes_keyr4().write(|w| w.bits(word)),
+                    5 => self.aes.aes_keyr5().write(|w| w.bits(word)),
+                    6 => self.aes.aes_keyr6().write(|w| w.bits(word)),
+                    _ => self.aes.aes_keyr7().write(|w| w.bits(word)),
+                };
+            }
+        }
+        while self.aes.aes_sr().read().keyvalid().bit_is_clear() {
+            asm::nop();
+        }
+    }
+
+    fn write_iv(&mut self, iv: &[u8; BLOCK_LEN]) {
+        let word = |idx: usize| {
+            u32::from_be_bytes([iv[4 * idx], iv[4 * idx + 1], iv[4 * idx + 2], iv[4 * idx + 3]])
+        };
+        unsafe {
+            self.aes.aes_ivr3().write(|w| w.bits(word(0)));
+            self.aes.aes_ivr2().write(|w| w.bits(word(1)));
+            self.aes.aes_ivr1().write(|w| w.bits(word(2)));
+            self.aes.aes_ivr0().write(|w| w.bits(word(3)));
+        }
+    }
+
+    fn write_block(&mut self, input: &[u8; BLOCK_LEN]) {
+        for chunk in input.chunks_exact(4) {
+            let word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            self.aes.aes_dinr().write(|w| unsafe { w.bits(word) });
+        }
+    }
+
+    fn read_block(&mut self, output: &mut [u8; BLOCK_LEN]) {
+        for chunk in output.chunks_exact_mut(4) {
+            chunk.copy_from_slice(&self.aes.aes_doutr().read().bits().to_le_bytes());
+        }
+        self.clear_complete();
+    }
+
+    #[inline]
+    fn is_complete(&self) -> bool {
+        self.aes.aes_isr().read().ccf().bit_is_set()
+    }
+
+    #[inline]
+    fn clear_complete(&self) {
+        self.aes.aes_icr().write(|w| w.ccf().set_bit());
+    }
+
+    fn listen(&self) {
+        self.aes.aes_ier().modify(|_, w| w.ccfie().set_bit());
+    }
+}
+
+/// `chunk` padded with zeros to a whole block.
+fn padded(chunk: &[u8]) -> [u8; BLOCK_LEN] {
+    let mut block = [0; BLOCK_LEN];
+    block[..chunk.len()].copy_from_slice(chunk);
+    block
+}
+
+fn check_lengths(input: &[u8], output: &[u8]) -> Result<(), Error> {
+    if input.len() != output.len() || !input.len().is_multiple_of(BLOCK_LEN) {
+        return Err(Error::DataLength);
+    }
+    Ok(())
+}
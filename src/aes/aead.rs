@@ -0,0 +1,86 @@
+//! RustCrypto `aead` traits for AES-GCM.
+//!
+//! [`Aes128Gcm`] and [`Aes256Gcm`] implement [`AeadInPlace`] with
+//! [`Aes::gcm_encrypt`] and [`Aes::gcm_decrypt`], with 96-bit nonces and
+//! 128-bit tags. Like the block ciphers, they borrow the driver through a
+//! [`RefCell`]; an operation fails with [`aead::Error`] if it is already
+//! borrowed.
+
+use core::cell::RefCell;
+
+use ::aead::{
+    consts::{U0, U12, U16},
+    AeadCore, AeadInPlace, Nonce, Tag,
+};
+use zeroize::Zeroize;
+
+use super::{Aes, NONCE_LEN, TAG_LEN};
+
+macro_rules! aes_gcm {
+    ($(#[$doc:meta])* $name:ident, $key_len:expr) => {
+        $(#[$doc])*
+        ///
+        /// The key is wiped when the cipher is dropped.
+        pub struct $name<'d> {
+            aes: &'d RefCell<Aes>,
+            key: [u8; $key_len],
+        }
+
+        impl<'d> $name<'d> {
+            pub fn new(aes: &'d RefCell<Aes>, key: &[u8; $key_len]) -> Self {
+                Self { aes, key: *key }
+            }
+        }
+
+        impl AeadCore for $name<'_> {
+            type NonceSize = U12;
+            type TagSize = U16;
+            type CiphertextOverhead = U0;
+        }
+
+        impl AeadInPlace for $name<'_> {
+            fn encrypt_in_place_detached(
+                &self,
+                nonce: &Nonce<Self>,
+                associated_data: &[u8],
+                buffer: &mut [u8],
+            ) -> Result<Tag<Self>, ::aead::Error> {
+                let nonce: &[u8; NONCE_LEN] = nonce.as_slice().try_into().unwrap();
+                let mut aes = self.aes.try_borrow_mut().map_err(|_| ::aead::Error)?;
+                let tag = aes
+                    .gcm_encrypt(&self.key, nonce, associated_data, buffer)
+                    .map_err(|_| ::aead::Error)?;
+                Ok(tag.into())
+            }
+
+            fn decrypt_in_place_detached(
+                &self,
+                nonce: &Nonce<Self>,
+                associated_data: &[u8],
+                buffer: &mut [u8],
+                tag: &Tag<Self>,
+            ) -> Result<(), ::aead::Error> {
+                let nonce: &[u8; NONCE_LEN] = nonce.as_slice().try_into().unwrap();
+                let tag: &[u8; TAG_LEN] = tag.as_slice().try_into().unwrap();
+                let mut aes = self.aes.try_borrow_mut().map_err(|_| ::aead::Error)?;
+                aes.gcm_decrypt(&self.key, nonce, associated_data, buffer, tag)
+                    .map_err(|_| ::aead::Error)
+            }
+        }
+
+        impl Drop for $name<'_> {
+            fn drop(&mut self) {
+                self.key.zeroize();
+            }
+        }
+    };
+}
+
+aes_gcm!(
+    /// AES-128-GCM on the AES coprocessor.
+    Aes128Gcm, 16
+);
+aes_gcm!(
+    /// AES-256-GCM on the AES coprocessor.
+    Aes256Gcm, 32
+);
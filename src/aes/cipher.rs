@@ -0,0 +1,111 @@
+//! RustCrypto `cipher` block cipher traits.
+//!
+//! [`Aes128`] and [`Aes256`] implement [`BlockEncrypt`] and [`BlockDecrypt`]
+//! with the peripheral in ECB mode, so block modes built on the `cipher`
+//! traits run on the AES coprocessor. The traits take the cipher by shared
+//! reference, so the ciphers borrow the driver through a [`RefCell`].
+
+use core::cell::RefCell;
+
+use ::cipher::{
+    consts::{U1, U16, U32},
+    inout::InOut,
+    Block, BlockBackend, BlockCipher, BlockClosure, BlockDecrypt, BlockEncrypt, BlockSizeUser,
+    KeySizeUser, ParBlocksSizeUser,
+};
+use zeroize::Zeroize;
+
+use super::{Aes, Chaining, Direction, BLOCK_LEN};
+
+/// Runs `f` with the peripheral set up for ECB in `direction`.
+///
+/// # Panics
+///
+/// Panics if the driver is already borrowed.
+fn with_backend(
+    aes: &RefCell<Aes>,
+    key: &[u8],
+    direction: Direction,
+    f: impl BlockClosure<BlockSize = U16>,
+) {
+    let mut aes = aes.borrow_mut();
+    aes.start(key, Chaining::Ecb, direction).expect("AES key is 16 or 32 bytes");
+    f.call(&mut Backend(&mut aes));
+    aes.stop();
+}
+
+/// Processes the blocks handed over by a [`BlockClosure`].
+struct Backend<'a>(&'a mut Aes);
+
+impl BlockSizeUser for Backend<'_> {
+    type BlockSize = U16;
+}
+
+impl ParBlocksSizeUser for Backend<'_> {
+    type ParBlocksSize = U1;
+}
+
+impl BlockBackend for Backend<'_> {
+    fn proc_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+        let mut input = [0; BLOCK_LEN];
+        input.copy_from_slice(block.get_in());
+        let mut output = [0; BLOCK_LEN];
+        self.0.process_block(&input, &mut output);
+        block.get_out().copy_from_slice(&output);
+    }
+}
+
+macro_rules! block_cipher {
+    ($(#[$doc:meta])* $name:ident, $key_size:ty, $key_len:expr) => {
+        $(#[$doc])*
+        ///
+        /// The key is wiped when the cipher is dropped.
+        pub struct $name<'d> {
+            aes: &'d RefCell<Aes>,
+            key: [u8; $key_len],
+        }
+
+        impl<'d> $name<'d> {
+            pub fn new(aes: &'d RefCell<Aes>, key: &[u8; $key_len]) -> Self {
+                Self { aes, key: *key }
+            }
+        }
+
+        impl KeySizeUser for $name<'_> {
+            type KeySize = $key_size;
+        }
+
+        impl BlockSizeUser for $name<'_> {
+            type BlockSize = U16;
+        }
+
+        impl BlockCipher for $name<'_> {}
+
+        impl BlockEncrypt for $name<'_> {
+            fn encrypt_with_backend(&self, f: impl BlockClosure<BlockSize = U16>) {
+                with_backend(self.aes, &self.key, Direction::Encrypt, f);
+            }
+        }
+
+        impl BlockDecrypt for $name<'_> {
+            fn decrypt_with_backend(&self, f: impl BlockClosure<BlockSize = U16>) {
+                with_backend(self.aes, &self.key, Direction::Decrypt, f);
+            }
+        }
+
+        impl Drop for $name<'_> {
+            fn drop(&mut self) {
+                self.key.zeroize();
+            }
+        }
+    };
+}
+
+block_cipher!(
+    /// AES-128 on the AES coprocessor.
+    Aes128, U16, 16
+);
+block_cipher!(
+    /// AES-256 on the AES coprocessor.
+    Aes256, U32, 32
+);
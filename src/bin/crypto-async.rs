@@ -0,0 +1,112 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Hashes a message, signs the digest and encrypts it with the async drivers
+// while a heartbeat task keeps ticking every millisecond. The tick count
+// logged after each step shows the executor kept running meanwhile.
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use stm32wba::stm32wba55::{self, interrupt};
+use stm32wba55cg::{
+    aes::{self, Chaining, Direction},
+    hash::{self, Algorithm},
+    pka::{self, curve, Pka, SigningKey},
+    Aes, Hash, Rng, Uint,
+};
+use {defmt_rtt as _, panic_probe as _};
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_time::{Duration, Ticker};
+
+const MESSAGE: &[u8] = b"sample";
+const KEY: [u8; 16] = [
+    0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf, 0x4f, 0x3c,
+];
+
+static TICKS: AtomicU32 = AtomicU32::new(0);
+
+#[interrupt]
+fn PKA() {
+    pka::on_interrupt();
+}
+
+#[interrupt]
+fn HASH() {
+    hash::on_interrupt();
+}
+
+#[interrupt]
+fn AES() {
+    aes::on_interrupt();
+}
+
+#[embassy_executor::task]
+async fn heartbeat() {
+    let mut ticker = Ticker::every(Duration::from_millis(1));
+    loop {
+        ticker.next().await;
+        TICKS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    // Time driver for the heartbeat
+    let _ = embassy_stm32::init(Default::default());
+    let p = stm32wba55::Peripherals::take().unwrap();
+
+    let mut pka = Pka::new(p.PKA, &p.RCC, &p.RNG);
+    let mut hash = Hash::new(p.HASH, &p.RCC);
+    let mut aes = Aes::new(p.AES, &p.RCC);
+    let mut rng = Rng::new(&p.RNG);
+    pka.enable_interrupt();
+    hash.enable_interrupt();
+    aes.enable_interrupt();
+    info!("Drivers initialized");
+
+    spawner.spawn(heartbeat().unwrap());
+
+    let curve = curve::NIST_P256;
+    let signing_key = match SigningKey::generate(&mut pka, &mut rng, &curve) {
+        Ok(key) => key,
+        Err(e) => {
+            info!("Error during key generation: {:?}", e);
+            return;
+        }
+    };
+
+    let mut digest = [0; 32];
+    hash.digest_async(Algorithm::Sha256, MESSAGE, &mut digest).await;
+    info!("SHA-256: {:#X}, ticks: {}", digest, TICKS.load(Ordering::Relaxed));
+
+    let hash_uint = Uint::<8>::from_be_bytes(&digest).unwrap();
+    match signing_key.sign_async(&mut pka, &mut rng, &hash_uint).await {
+        Ok((r_sign, s_sign)) => {
+            info!("ECDSA Signature r: {}", r_sign);
+            info!("ECDSA Signature s: {}", s_sign);
+            info!("Signed, ticks: {}", TICKS.load(Ordering::Relaxed));
+
+            match signing_key
+                .verifying_key()
+                .verify_async(&mut pka, &r_sign, &s_sign, &hash_uint)
+                .await
+            {
+                Ok(()) => info!("Signature verified, ticks: {}", TICKS.load(Ordering::Relaxed)),
+                Err(e) => info!("Error during ECDSA verification: {:?}", e),
+            }
+        }
+        Err(e) => info!("Error during ECDSA signing: {:?}", e),
+    }
+
+    let mut ciphertext = [0; 32];
+    match aes.start(&KEY, Chaining::Ecb, Direction::Encrypt) {
+        Ok(()) => match aes.process_async(&digest, &mut ciphertext).await {
+            Ok(()) => info!("AES-128 ECB: {:#X}, ticks: {}", ciphertext, TICKS.load(Ordering::Relaxed)),
+            Err(e) => info!("Error during AES encryption: {:?}", e),
+        },
+        Err(e) => info!("Error loading the AES key: {:?}", e),
+    }
+    aes.stop();
+}
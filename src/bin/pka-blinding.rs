@@ -0,0 +1,119 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Times the operations the `blinding` feature protects, in core cycles. Build
+// once with and once without the feature to compare:
+//   cargo run --release --bin pka-blinding
+//   cargo run --release --bin pka-blinding --features blinding
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{curve, EphemeralSecret, Pka, RsaCrtKey, SigningKey},
+    Rng, Uint,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::{asm, peripheral::DWT};
+use defmt::info;
+
+// SHA-256("sample")
+const HASH: Uint<8> = Uint::from_be_hex(
+    "af2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf",
+);
+
+// 1024-bit RSA test key, e = 65537
+const RSA_E: u32 = 65537;
+const RSA_N: Uint<32> = Uint::from_be_hex(
+    "ac9ae08df30eed9eb465be82af1b166782f4053ce308cd5c71f89424b67e2cb4\
+     19799abf578e227f31303f455c513c383a46118a49956a2f6d334c27e294a30d\
+     14963e929139d7e78358ffae37c5981a7f734e37b73c2a8687a326f55efb6613\
+     5712464900943858bb20eef8304fbb7379c12a26026e9b80028f5e204d8917db",
+);
+const RSA_D: Uint<32> = Uint::from_be_hex(
+    "5f478b67b0617038285af5c44c0706e212fec3196e3640aea45754e7c3397951\
+     ddb22c032dc0cf38291df9ff3ecbb51350cfb7050043937f24a7a98566e527ef\
+     bb6c349109db25c509c610622222df1500fd8e0baed6123aedea757027753eac\
+     347ed19c63a3d9ad45b7821a354cc823c1e79f805b849c95afdbf2d9a19c9d81",
+);
+const RSA_P: Uint<16> = Uint::from_be_hex(
+    "d64aa4c2ef4812ae88b8be004ba1f95313675289499b2bb41eec50dacbd7e2f1\
+     63cd3d4fd779be0257961c85ff2611913c5ebdfc29f436090f384af39063ddc1",
+);
+const RSA_Q: Uint<16> = Uint::from_be_hex(
+    "ce332527fdd9af6d1b3d72e4cf4bc8abf386ac0e9334b4bd6b6dc3862d91c9e6\
+     52f2cb16e2eb3680c420e5dd90b5106163037da630c8a1a3b9f25a4604eed49b",
+);
+const RSA_DP: Uint<16> = Uint::from_be_hex(
+    "409d373af40d865c8c2b30574476ed87c83502651505add2dcf422992697ccfd\
+     3e3b977e2899b3da5ef08b5f3805118cfa6812df230358fbb8541ffd7b0f31c1",
+);
+const RSA_DQ: Uint<16> = Uint::from_be_hex(
+    "58a5b650a61ac307661199e67294431661a0c13196e9aed64c6c2905f36b5ce0\
+     6f42c03afb9d6196243b7c772fb217f3b64c96e52ecde267f01617e74851a107",
+);
+const RSA_QINV: Uint<16> = Uint::from_be_hex(
+    "453dbc66e9316f4383785c66c5885df9f6f9e2881ca0aac8821628957c17d125\
+     cc5621ab73c4090466c7e0048fa37e2a21211ccfa282890db770bf49e3173ab1",
+);
+const RSA_MESSAGE: Uint<32> = Uint::from_be_hex(
+    "0000000000000000000000000000000000000000000000000000000000000000\
+     0000000000000000000000000000000000000000000000000000000000000000\
+     0000000000000000000000000000000000000000000000000000000000000000\
+     000000000000000000000000000000000053616d706c65206d657373616765",
+);
+// RSA_MESSAGE^RSA_D mod RSA_N
+const RSA_SIGNATURE: Uint<32> = Uint::from_be_hex(
+    "0281fed68392f105ee9bc3e33e9d9fe776ee10763152460bbf321b973e63858d\
+     c141d65a9a4359d4238795023531b4a4e371c4719e5ee15ad5caebe19ee1b690\
+     9e6e573c6df5f86b5938320ca5ea0ef3c6f330ce09cf756156dc14299abb6f68\
+     3cae09fe1befdef8bf7b847b7e087b2cc2ed7afc0dc56dd02b490f0260842351",
+);
+
+#[entry]
+fn main() -> ! {
+    let mut cp = cortex_m::Peripherals::take().unwrap();
+    cp.DCB.enable_trace();
+    cp.DWT.enable_cycle_counter();
+
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let mut pka = Pka::new(p.PKA, &p.RCC, &p.RNG);
+    let mut rng = Rng::new(&p.RNG);
+    info!("PKA Initialized, blinding: {}", cfg!(feature = "blinding"));
+
+    let curve = curve::NIST_P256;
+    let signing_key = SigningKey::generate(&mut pka, &mut rng, &curve).unwrap();
+
+    let start = DWT::cycle_count();
+    let signature = signing_key.sign(&mut pka, &mut rng, &HASH);
+    let cycles = DWT::cycle_count().wrapping_sub(start);
+    match signature {
+        Ok((r_sign, s_sign)) => {
+            let verified = signing_key.verifying_key().verify(&mut pka, &r_sign, &s_sign, &HASH);
+            info!("ECDSA P-256 sign: {} cycles, verified: {}", cycles, verified == Ok(()));
+        }
+        Err(e) => info!("Error during ECDSA signing: {:?}", e),
+    }
+
+    let secret = EphemeralSecret::random(&mut rng, &curve);
+    let peer = EphemeralSecret::random(&mut rng, &curve).public_key(&mut pka).unwrap();
+    let start = DWT::cycle_count();
+    let shared = secret.diffie_hellman(&mut pka, &peer);
+    let cycles = DWT::cycle_count().wrapping_sub(start);
+    info!("ECDH P-256: {} cycles, ok: {}", cycles, shared.is_ok());
+
+    let start = DWT::cycle_count();
+    let signature = pka.rsa_private(&RSA_MESSAGE, &RSA_D, RSA_E, &RSA_N);
+    let cycles = DWT::cycle_count().wrapping_sub(start);
+    info!("RSA-1024 private: {} cycles, match: {}", cycles, signature == Ok(RSA_SIGNATURE));
+
+    let key = RsaCrtKey::new(RSA_N, RSA_E, RSA_P, RSA_Q, RSA_DP, RSA_DQ, RSA_QINV);
+    let start = DWT::cycle_count();
+    let signature = pka.rsa_crt_sign(&key, &RSA_MESSAGE);
+    let cycles = DWT::cycle_count().wrapping_sub(start);
+    info!("RSA-1024 CRT: {} cycles, match: {}", cycles, signature == Ok(RSA_SIGNATURE));
+
+    loop {
+        asm::nop();
+    }
+}
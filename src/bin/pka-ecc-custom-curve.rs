@@ -0,0 +1,104 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{AffinePoint, CurveError, CustomCurve, EccMulMode, Pka, Sign, SigningKey},
+    Rng, Uint,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::asm;
+use defmt::info;
+
+// nist P-224, as it would arrive in a key blob
+const P224_B: [u8; 28] = [
+    0xb4, 0x05, 0x0a, 0x85, 0x0c, 0x04, 0xb3, 0xab, 0xf5, 0x41, 0x32, 0x56, 0x50, 0x44,
+    0xb0, 0xb7, 0xd7, 0xbf, 0xd8, 0xba, 0x27, 0x0b, 0x39, 0x43, 0x23, 0x55, 0xff, 0xb4,
+];
+const P224_P: [u8; 28] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+];
+const P224_GX: [u8; 28] = [
+    0xb7, 0x0e, 0x0c, 0xbd, 0x6b, 0xb4, 0xbf, 0x7f, 0x32, 0x13, 0x90, 0xb9, 0x4a, 0x03,
+    0xc1, 0xd3, 0x56, 0xc2, 0x11, 0x22, 0x34, 0x32, 0x80, 0xd6, 0x11, 0x5c, 0x1d, 0x21,
+];
+const P224_GY: [u8; 28] = [
+    0xbd, 0x37, 0x63, 0x88, 0xb5, 0xf7, 0x23, 0xfb, 0x4c, 0x22, 0xdf, 0xe6, 0xcd, 0x43,
+    0x75, 0xa0, 0x5a, 0x07, 0x47, 0x64, 0x44, 0xd5, 0x81, 0x99, 0x85, 0x00, 0x7e, 0x34,
+];
+const P224_N: [u8; 28] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x16, 0xa2, 0xe0, 0xb8, 0xf0, 0x3e, 0x13, 0xdd, 0x29, 0x45, 0x5c, 0x5c, 0x2a, 0x3d,
+];
+
+// 2G on P-224
+const P224_2G_X: &str = "706A46DC76DCB76798E60E6D89474788D16DC18032D268FD1A704FA6";
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let pka = p.PKA;
+    let rcc = &p.RCC;
+    let rng = &p.RNG;
+
+    let mut pka = Pka::new(pka, rcc, rng);
+    let mut rng = Rng::new(rng);
+    info!("PKA Initialized");
+
+    let curve = match CustomCurve::from_be_bytes(
+        Sign::Neg, &[0x03], &P224_B, &P224_P, &P224_GX, &P224_GY, &P224_N,
+    ) {
+        Ok(curve) => curve,
+        Err(e) => {
+            info!("Error loading curve: {:?}", e);
+            loop {
+                asm::nop();
+            }
+        }
+    };
+    info!("Loaded a {}-bit curve", curve.modulus.bits());
+
+    // A base point moved off the curve must be caught when loading
+    let mut bad_gy = P224_GY;
+    bad_gy[27] ^= 1;
+    info!(
+        "Bad base point rejected: {}",
+        CustomCurve::from_be_bytes(Sign::Neg, &[0x03], &P224_B, &P224_P, &P224_GX, &bad_gy, &P224_N)
+            == Err(CurveError::BasePointNotOnCurve)
+    );
+
+    // Point check and scalar multiplication
+    let g = AffinePoint::generator(&curve);
+    info!("G on curve: {}", pka.ecc_check(&curve, &g).is_ok());
+    let two_g_x: Uint<20> = Uint::from_be_hex(P224_2G_X);
+    match pka.ecc_mul(&curve, &Uint::from_u32(2), &g, EccMulMode::Fast) {
+        Ok(point) => info!("2G match: {}", point.x == two_g_x),
+        Err(e) => info!("Error during Ecc Multiplication: {:?}", e),
+    }
+    let n_minus_one = curve.prime_order.sbb(&Uint::ONE).0;
+    let minus_g = AffinePoint::new(g.x, g.y.neg_mod(&curve.modulus));
+    info!(
+        "(n - 1) G = -G: {}",
+        pka.ecc_mul(&curve, &n_minus_one, &g, EccMulMode::Fast) == Ok(minus_g)
+    );
+
+    // ECDSA with a key generated on the runtime curve
+    let hash: Uint<20> = Uint::from_be_hex("c0ffee");
+    match SigningKey::generate(&mut pka, &mut rng, &curve) {
+        Ok(signing_key) => match signing_key.sign(&mut pka, &mut rng, &hash) {
+            Ok((r_sign, s_sign)) => {
+                let verified = signing_key.verifying_key().verify(&mut pka, &r_sign, &s_sign, &hash);
+                info!("ECDSA sign/verify: {:?}", verified);
+            }
+            Err(e) => info!("Error during ECDSA signing: {:?}", e),
+        },
+        Err(e) => info!("Error during key generation: {:?}", e),
+    }
+
+    loop {
+        asm::nop();
+    }
+}
@@ -3,15 +3,18 @@
 
 // Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
 // use stm32wba::stm32wba55;
-use stm32wba::stm32wba55::{self,pka::pka_cr::MODE};
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{curve, AffinePoint, EccMulMode, EllipticCurve, Error, Pka},
+    Uint,
+};
 use {defmt_rtt as _, panic_probe as _};
 use cortex_m_rt::entry;
 use cortex_m::asm;
 use defmt::info;
-use core::{
-    mem::size_of,
-    ptr::{read_volatile, write_volatile},
-};
+
+// Number of random scalars checked against the software reference
+const ROUNDS: usize = 8;
 
 #[entry]
 fn main() -> ! {
@@ -24,430 +27,160 @@ fn main() -> ! {
     info!("PKA Initialized");
 
     let curve = curve::NIST_P256;
-    let mut result_x: [u32; 8] = [0; 8];
-    let mut result_y: [u32; 8] = [0; 8];
-    let mut scalar: [u32; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
-    
-    // Perform ECDSA Signing using PKA
-    match pka.ecc_multiplication(&curve, &mut scalar, &POINT_X, &POINT_Y, &mut result_x, &mut result_y) {
-        Ok(_) => {},
-        Err(e) => {
-            info!("Error during Ecc Multiplication: {:?}", e);
+    let g = AffinePoint::generator(&curve);
+
+    // 1 * G must give G back
+    match pka.ecc_mul(&curve, &Uint::ONE, &g, EccMulMode::Fast) {
+        Ok(point) => info!("1 * G = ({}, {}) match: {}", point.x, point.y, point == g),
+        Err(e) => info!("Error during Ecc Multiplication: {:?}", e),
+    }
+
+    // Invalid inputs must be rejected
+    let off_curve = AffinePoint::new(g.x, g.y.add_mod(&Uint::ONE, &curve.modulus));
+    info!(
+        "Point at infinity rejected: {}",
+        pka.ecc_mul(&curve, &Uint::ONE, &AffinePoint::IDENTITY, EccMulMode::Fast)
+            == Err(Error::PointAtInfinity)
+    );
+    info!(
+        "Point off the curve rejected: {}",
+        pka.ecc_mul(&curve, &Uint::ONE, &off_curve, EccMulMode::Fast) == Err(Error::NotOnCurve)
+    );
+
+    let mut passed = 0;
+    for round in 0..ROUNDS {
+        let scalar = random_scalar(rng, &curve.prime_order);
+        let expected = software_mul(&curve, &scalar, &g);
+
+        let fast = pka.ecc_mul(&curve, &scalar, &g, EccMulMode::Fast);
+        let protected = pka.ecc_mul(&curve, &scalar, &g, EccMulMode::Protected);
+        let ok = fast == Ok(expected) && protected == Ok(expected);
+        if ok {
+            passed += 1;
+        }
+        info!("Round {}: k = {} ok: {}", round, scalar, ok);
+        if !ok {
+            info!("Expected: ({}, {})", expected.x, expected.y);
+            info!("Fast: {:?}", fast);
+            info!("Protected: {:?}", protected);
         }
     }
+    info!("{}/{} random scalars match the software reference", passed, ROUNDS);
 
     loop {
         asm::nop();
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub enum Error {
-    Address,
-    Ram,
-    Mode {mode: u8},
-    Unknown {bits: u32},
-    Busy,
-}
-
-impl Error {
-    const fn from_raw(raw: u32) -> Result<(), Error> {
-        match raw {
-            0 => Ok(()),
-            _ => Err(Error::Unknown { bits: raw }),
-        }
-    }
-
-    const fn mode(mode: u8) -> Result<(), Error> {
-        Err(Error::Mode { mode })
-    }
-}
-
-/// PKA operation codes.
-#[derive(Debug)]
-#[repr(u8)]
-#[allow(dead_code)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-enum PkaOpcode {
-    /// Montgomery parameter computation then modular exponentiation.
-    MontgomeryParameterExponentiation = 0b000000,
-    /// Montgomery parameter computation only.
-    MontgomeryParameter = 0b000001,
-    /// Modular exponentiation only (Montgomery parameter must be loaded first).
-    ModularExponentiation = 0b000010,
-    /// Montgomery parameter computation then ECC scalar multiplication.
-    MontgomeryParameterEcc = 0b100000,
-    /// ECC scalar multiplication only (Montgomery parameter must be loaded first).
-    EccScalar = 0b100010,
-    /// ECC complete addition.
-    EccAddition = 0b100011,
-    /// ECC complete addition.
-    EccLadder = 0b100111,
-    /// ECC projective to affine
-    EccProjectiveAffine = 0b101111,
-    /// ECDSA signing.
-    EcdsaSign = 0b100100,
-    /// ECDSA verification.
-    EcdsaVerify = 0b100110,
-    /// Point on elliptic curve Fp check.
-    Point = 0b101000,
-    /// RSA CRT exponentiation.
-    RsaCrt = 0b000111,
-    /// Modular inversion.
-    ModularInversion = 0b001000,
-    /// Arithmetic addition.
-    ArithmeticAdd = 0b001001,
-    /// Arithmetic subtraction.
-    ArithmeticSub = 0b001010,
-    /// Arithmetic multiplication.
-    ArithmeticMul = 0b001011,
-    /// Arithmetic comparison.
-    ArithmeticCmp = 0b001100,
-    /// Modular reduction.
-    ModularRed = 0b001101,
-    /// Modular addition.
-    ModularAdd = 0b001110,
-    /// Modular subtraction.
-    ModularSub = 0b001111,
-    /// Montgomery multiplication.
-    MontgomeryMul = 0b010000,
-}
-
-impl From<PkaOpcode> for u8 {
-    fn from(x: PkaOpcode) -> Self {
-        x as u8
-    }
-}
-
-const BASE: usize = 0x520C_2000;
-const PKA_RAM_OFFSET: usize = 0x400; 
-const RAM_BASE: usize = BASE + PKA_RAM_OFFSET;
-const RAM_NUM_DW: usize = 667;
-
-// PKA RAM locations for exponentiation
-const PRIME_LENGTH_OFFSET: usize = BASE + 0x400;
-const MODULUS_LENGTH_OFFSET: usize = BASE + 0x408;
-const COEF_A_SIGN_OFFSET: usize = BASE + 0x410;
-const COEF_A_OFFSET: usize = BASE + 0x418;
-const COEF_B_OFFSET: usize = BASE + 0x520;
-const MODULUS_OFFSET: usize = BASE + 0x1088;
-const SCALAR_OFFSET: usize = BASE + 0x12A0;
-const POINT_X_OFFSET: usize = BASE + 0x578;
-const POINT_Y_OFFSET: usize = BASE + 0x470;
-const PRIME_OFFSET: usize = BASE + 0xF88;
-const RESULT_X_OFFSET: usize = BASE + 0x578;
-const RESULT_Y_OFFSET: usize = BASE + 0x5D0;
-const RESULT_ERROR_OFFSET: usize = BASE + 0x680;
-
-const POINT_X: [u32; 8] = [
-    0x6b17d1f2, 0xe12c4247, 0xf8bce6e5, 0x63a440f2, 
-    0x77037d81, 0x2deb33a0, 0xf4a13945, 0xd898c296,
-];
-
-const POINT_Y: [u32; 8] = [
-    0x4fe342e2, 0xfe1a7f9b, 0x8ee7eb4a, 0x7c0f9e16, 
-    0x2bce3357, 0x6b315ece, 0xcbb64068, 0x37bf51f5,
-];
-
-/// PKA driver.
-#[derive(Debug)]
-pub struct Pka {
-    pka: stm32wba55::PKA,
-}
-
-impl Pka {
-    pub fn new(pka: stm32wba55::PKA, rcc: &stm32wba55::RCC, rng: &stm32wba55::RNG) -> Self {
-        // Enable HSE (External High-Speed Clock) as a stable clock source
-        rcc.rcc_cr().modify(|_, w| w.hseon().set_bit());
-        while rcc.rcc_cr().read().hserdy().bit_is_clear() {
-            asm::nop();
-        }
-
-        // Configure RNG clock
-        rcc.rcc_ccipr2().write(|w| w.rngsel().b_0x2());
-        
-        // Enable RNG clock on AHB2
-        rcc.rcc_ahb2enr().modify(|_, w| w.rngen().set_bit());
-        while rcc.rcc_ahb2enr().read().rngen().bit_is_clear() {
-            asm::nop();
-        }
-
-        // Configure RNG 
-        rng.rng_cr().write(|w| w
-            .rngen().clear_bit()
-            .condrst().set_bit()
-            .configlock().clear_bit() 
-            .nistc().clear_bit()   
-            .ced().clear_bit() 
-        );
-
-        // Clear CONDRST while keeping RNGEN disabled
-        rng.rng_cr().modify(|_, w| w.condrst().clear_bit());
-
-        // Enable RNG with interrupts
-        rng.rng_cr().modify(|_, w| w
-            .rngen().set_bit()
-            .ie().set_bit()
-        );
-        
-        while rng.rng_sr().read().drdy().bit_is_clear() {
-            asm::nop();
-        }
-
-        // Enable PKA peripheral clock
-        rcc.rcc_ahb2enr().modify(|_, w| w.pkaen().set_bit());
-
-        // Reset PKA before enabling (sometimes helps with initialization)
-        pka.pka_cr().modify(|_, w| w.en().clear_bit());
-        for _ in 0..10 {
-            asm::nop();
-        }
-
-        // Enable PKA peripheral
-        pka.pka_cr().modify(|_, w| w.en().set_bit());
-    
-        // Wait for PKA to initialize
-        while pka.pka_sr().read().initok().bit_is_clear() {
-            asm::nop();
-        }
-        // info!("PKA initialized successfully!");
-
-        Self { pka }
-    }
-
-    /// Returns `true` if the PKA is enabled.
-    #[inline]
-    pub fn is_enabled(&self) -> bool {
-        self.pka.pka_cr().read().en().bit_is_set()
-    }
-
-    #[inline]
-    fn clear_all_flags(&mut self) {
-        self.pka.pka_clrfr().write(|w| {
-            w.addrerrfc().set_bit();
-            w.ramerrfc().set_bit();
-            w.procendfc().set_bit()
-        });
-    }
-
-    fn zero_ram(&mut self) {
-        (0..RAM_NUM_DW)
-            .into_iter()
-            .for_each(|dw| unsafe { write_volatile((dw * 4 + RAM_BASE) as *mut u32, 0) });
-    }
-
-    unsafe fn write_ram(&mut self, offset: usize, buf: &[u32]) {
-        debug_assert_eq!(offset % 4, 0);
-        debug_assert!(offset + buf.len() * size_of::<u32>() < 0x520C_33FF);
-        buf.iter().rev().enumerate().for_each(|(idx, &dw)| {
-            write_volatile((offset + idx * size_of::<u32>()) as *mut u32, dw)
-        });
-    }
-    
-    unsafe fn read_ram(&mut self, offset: usize, buf: &mut [u32]) {
-        debug_assert_eq!(offset % 4, 0);
-        debug_assert!(offset + buf.len() * size_of::<u32>() < 0x520C_33FF);
-        buf.iter_mut().rev().enumerate().for_each(|(idx, dw)| {
-            *dw = read_volatile((offset + idx * size_of::<u32>()) as *const u32);
-        });
-    }
-
-    #[inline]
-    unsafe fn start_process(&mut self, opcode: PkaOpcode) {
-        self.pka.pka_cr().write(|w| {
-            w.addrerrie().set_bit();
-            w.ramerrie().set_bit();
-            w.procendie().set_bit();
-            w.mode().bits(opcode as u8);
-            w.start().set_bit();
-            w.en().set_bit()
-        });
-
-    }
-
-    pub fn ecc_multiplication<const MODULUS_SIZE: usize, const OPERAND_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
-        &mut self,
-        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE, OPERAND_SIZE>,
-        scalar: &[u32; OPERAND_SIZE],
-        point_x: &[u32; MODULUS_SIZE],
-        point_y: &[u32; MODULUS_SIZE],
-        result_x: &mut [u32; MODULUS_SIZE],
-        result_y: &mut [u32; MODULUS_SIZE],
-    ) -> Result<(), Error> {
-        self.clear_all_flags();
-        self.ecc_multiplication_start(curve, scalar, point_x, point_y)?;
-        self.ecc_multiplication_result(result_x, result_y)
-    }
-
-    pub fn ecc_multiplication_start<const MODULUS_SIZE: usize, const OPERAND_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
-        &mut self,
-        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE, OPERAND_SIZE>,
-        scalar: &[u32; OPERAND_SIZE],
-        point_x: &[u32; MODULUS_SIZE],
-        point_y: &[u32; MODULUS_SIZE],
-    ) -> Result<(), Error> {
-        self.zero_ram();
-        let n_length: u32 = (PRIME_ORDER_SIZE * size_of::<u32>() * 8) as u32;
-        let p_length: u32 = (MODULUS_SIZE * size_of::<u32>() * 8) as u32;
-
-        unsafe {
-            write_volatile(PRIME_LENGTH_OFFSET as *mut u32, n_length);
-            write_volatile(MODULUS_LENGTH_OFFSET as *mut u32, p_length);
-            write_volatile(COEF_A_SIGN_OFFSET as *mut u32, curve.coef_sign.into());
-            self.write_ram(COEF_A_OFFSET, &curve.coef_a);
-            self.write_ram(COEF_B_OFFSET, &curve.coef_b);
-            self.write_ram(MODULUS_OFFSET, &curve.modulus);
-            self.write_ram(PRIME_OFFSET, &curve.prime_order);
-            self.write_ram(POINT_X_OFFSET, point_x);
-            self.write_ram(POINT_Y_OFFSET, point_y);
-            self.write_ram(SCALAR_OFFSET, scalar);
-        }
-
-        let sr = self.pka.pka_sr().read();
-        if sr.addrerrf().bit_is_set() {
-            self.clear_all_flags();
-            Err(Error::Address)
-        } else if sr.ramerrf().bit_is_set() {
-            self.clear_all_flags();
-            Err(Error::Ram)
-        } else {
-            unsafe {
-               self.start_process(PkaOpcode::MontgomeryParameterEcc); 
-            }
-            Ok(())
-        }
-    }
-
-    pub fn ecc_multiplication_result<const MODULUS_SIZE: usize>(
-        &mut self,
-        result_x: &mut [u32; MODULUS_SIZE],
-        result_y: &mut [u32; MODULUS_SIZE],
-    ) -> Result<(), Error> {
-        let mode = self.pka.pka_cr().read().mode().bits();
-        if mode != PkaOpcode::MontgomeryParameterEcc as u8 {
-            return Error::mode(mode);
-        }
-        let sr = self.pka.pka_sr().read();
-        if sr.addrerrf().bit_is_set() {
-            self.clear_all_flags();
-            return Err(Error::Address)
-        } else if sr.ramerrf().bit_is_set() {
-            self.clear_all_flags();
-            return Err(Error::Ram)
-        } else if sr.procendf().bit_is_clear() {
-            info!("Waiting for operation to complete...");
-            while sr.procendf().bit_is_clear() {
+/// Draws a scalar in [1, n - 1] from the RNG.
+fn random_scalar<const N: usize>(rng: &stm32wba55::RNG, order: &Uint<N>) -> Uint<N> {
+    loop {
+        let mut words = [0u32; N];
+        for word in words.iter_mut() {
+            while rng.rng_sr().read().drdy().bit_is_clear() {
                 asm::nop();
             }
-            info!("Operation completed ({:?})", sr.procendf().bit_is_set() );
-        }
-
-        self.clear_all_flags();
-
-        unsafe {
-            self.read_ram(RESULT_X_OFFSET, result_x);
-            self.read_ram(RESULT_Y_OFFSET, result_y);
+            *word = rng.rng_dr().read().bits();
         }
-
-        let result: u32 = unsafe { read_volatile(RESULT_ERROR_OFFSET as *const u32) };
-        if result != 0 {
-            self.zero_ram();
+        let scalar = Uint::from_le_words(words);
+        if !scalar.is_zero() && scalar < *order {
+            return scalar;
         }
-        Error::from_raw(result)
     }
 }
 
-/// Sign bit for ECDSA coefficient signing and verification.
-#[repr(u32)]
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub enum Sign {
-    Pos = 0,
-    Neg = 1,
-}
-
-impl From<Sign> for u32 {
-    fn from(s: Sign) -> Self {
-        s as u32
+/// Double-and-add in software, used as the reference result.
+///
+/// Runs in Jacobian coordinates (x = X / Z^2, y = Y / Z^3) so that only the
+/// final conversion needs a modular inversion.
+fn software_mul<const M: usize, const N: usize>(
+    curve: &EllipticCurve<M, N>,
+    scalar: &Uint<N>,
+    point: &AffinePoint<M>,
+) -> AffinePoint<M> {
+    let p = &curve.modulus;
+    let a = curve.coef_a_mod_p();
+    let identity = (Uint::ONE, Uint::ONE, Uint::ZERO);
+    let (x, y, z) = (0..scalar.bits()).rev().fold(identity, |acc, idx| {
+        let acc = software_double(p, &a, &acc);
+        if scalar.bit(idx) {
+            software_add(p, &a, &acc, point)
+        } else {
+            acc
+        }
+    });
+    if z.is_zero() {
+        return AffinePoint::IDENTITY;
     }
+    let z_inv = z.inv_mod(p);
+    let z_inv2 = z_inv.mul_mod(&z_inv, p);
+    AffinePoint::new(x.mul_mod(&z_inv2, p), y.mul_mod(&z_inv2.mul_mod(&z_inv, p), p))
 }
 
-/// Elliptic curve.
-#[derive(Debug, PartialEq, Eq)]
-pub struct EllipticCurve<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize, const OPERAND_SIZE: usize> {
-    /// Curve coefficient a sign.
-    ///
-    /// **Note:** 0 for positive, 1 for negative.
-    pub coef_sign: Sign,
-    /// Curve coefficient |a|.
-    ///
-    /// **Note:** Absolute value, |a| < p.
-    pub coef_a: [u32; MODULUS_SIZE],
-    /// Curve coefficient b.
-    pub coef_b: [u32; MODULUS_SIZE],
-    /// Curve modulus value p.
-    ///
-    /// **Note:** Odd integer prime, 0 < p < 2<sup>640</sup>
-    pub modulus: [u32; MODULUS_SIZE],
-    /// Curve base point G coordinate x.
-    ///
-    /// **Note:** x < p
-    pub base_point_x: [u32; MODULUS_SIZE],
-    /// Curve base point G coordinate y.
-    ///
-    /// **Note:** y < p
-    pub base_point_y: [u32; MODULUS_SIZE],
-    /// Curve prime order n.
-    ///
-    /// **Note:** Integer prime.
-    pub prime_order: [u32; PRIME_ORDER_SIZE],
-    // Operand examples
-    pub operand_a: [u32; OPERAND_SIZE],
-    pub operand_b: [u32; OPERAND_SIZE],
+type Jacobian<const M: usize> = (Uint<M>, Uint<M>, Uint<M>);
+
+fn software_double<const M: usize>(p: &Uint<M>, a: &Uint<M>, q: &Jacobian<M>) -> Jacobian<M> {
+    let (x, y, z) = q;
+    if z.is_zero() || y.is_zero() {
+        return (Uint::ONE, Uint::ONE, Uint::ZERO);
+    }
+    let xx = x.mul_mod(x, p);
+    let yy = y.mul_mod(y, p);
+    let zz = z.mul_mod(z, p);
+    // S = 4 X Y^2, M = 3 X^2 + a Z^4
+    let s = x.mul_mod(&yy, p);
+    let s = s.add_mod(&s, p);
+    let s = s.add_mod(&s, p);
+    let m = xx
+        .add_mod(&xx, p)
+        .add_mod(&xx, p)
+        .add_mod(&a.mul_mod(&zz.mul_mod(&zz, p), p), p);
+    // X3 = M^2 - 2 S, Y3 = M (S - X3) - 8 Y^4, Z3 = 2 Y Z
+    let x3 = m.mul_mod(&m, p).sub_mod(&s, p).sub_mod(&s, p);
+    let yyyy = yy.mul_mod(&yy, p);
+    let yyyy2 = yyyy.add_mod(&yyyy, p);
+    let yyyy4 = yyyy2.add_mod(&yyyy2, p);
+    let y3 = m
+        .mul_mod(&s.sub_mod(&x3, p), p)
+        .sub_mod(&yyyy4.add_mod(&yyyy4, p), p);
+    let yz = y.mul_mod(z, p);
+    (x3, y3, yz.add_mod(&yz, p))
 }
 
-/// Pre-defined elliptic curves.
-pub mod curve {
-    use super::{
-        EllipticCurve,
-        Sign::{Neg, Pos},
-    };
-
-    /// nist P-256
-    pub const NIST_P256: EllipticCurve<8, 8, 8> = EllipticCurve {
-        coef_sign: Neg,
-        coef_a: [
-            0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000, 0x00000000,
-            0x00000003,
-        ],
-        coef_b: [
-            0x5ac635d8, 0xaa3a93e7, 0xb3ebbd55, 0x769886bc, 0x651d06b0, 0xcc53b0f6, 0x3bce3c3e, 
-            0x27d2604b
-        ],
-        modulus: [
-            0xffffffff, 0x00000001, 0x00000000, 0x00000000, 0x00000000, 0xffffffff, 0xffffffff,
-            0xffffffff,
-        ],
-        base_point_x: [
-            0x6b17d1f2, 0xe12c4247, 0xf8bce6e5, 0x63a440f2, 0x77037d81, 0x2deb33a0, 0xf4a13945,
-            0xd898c296,
-        ],
-        base_point_y: [
-            0x4fe342e2, 0xfe1a7f9b, 0x8ee7eb4a, 0x7c0f9e16, 0x2bce3357, 0x6b315ece, 0xcbb64068,
-            0x37bf51f5,
-        ],
-        prime_order: [
-            0xffffffff, 0x00000000, 0xffffffff, 0xffffffff, 0xbce6faad, 0xa7179e84, 0xf3b9cac2,
-            0xfc632551,
-        ],
-        operand_a : [
-            0xffffffff, 0x00000001, 0x00000000, 0x00000000, 0x00000000, 0xffffffff, 0xffffffff,
-            0xfffffffe
-        ],
-        operand_b : [
-            0xffffffff, 0x00000001, 0x00000000, 0x00000000, 0x00000000, 0xffffffff, 0xffffffff,
-            0xfffffff0
-        ],
-    };
+fn software_add<const M: usize>(
+    p: &Uint<M>,
+    a: &Uint<M>,
+    q: &Jacobian<M>,
+    r: &AffinePoint<M>,
+) -> Jacobian<M> {
+    let (x1, y1, z1) = q;
+    if z1.is_zero() {
+        return (r.x, r.y, Uint::ONE);
+    }
+    // U2 = x2 Z1^2, S2 = y2 Z1^3, H = U2 - X1, R = S2 - Y1
+    let zz = z1.mul_mod(z1, p);
+    let u2 = r.x.mul_mod(&zz, p);
+    let s2 = r.y.mul_mod(&zz.mul_mod(z1, p), p);
+    let h = u2.sub_mod(x1, p);
+    let rr = s2.sub_mod(y1, p);
+    if h.is_zero() {
+        return if rr.is_zero() {
+            software_double(p, a, q)
+        } else {
+            (Uint::ONE, Uint::ONE, Uint::ZERO)
+        };
+    }
+    // X3 = R^2 - H^3 - 2 X1 H^2, Y3 = R (X1 H^2 - X3) - Y1 H^3, Z3 = Z1 H
+    let hh = h.mul_mod(&h, p);
+    let hhh = hh.mul_mod(&h, p);
+    let v = x1.mul_mod(&hh, p);
+    let x3 = rr
+        .mul_mod(&rr, p)
+        .sub_mod(&hhh, p)
+        .sub_mod(&v, p)
+        .sub_mod(&v, p);
+    let y3 = rr
+        .mul_mod(&v.sub_mod(&x3, p), p)
+        .sub_mod(&y1.mul_mod(&hhh, p), p);
+    (x3, y3, z1.mul_mod(&h, p))
 }
@@ -3,249 +3,59 @@
 
 // Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
 // use stm32wba::stm32wba55;
-use stm32wba::stm32wba55::{self};
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{curve, AffinePoint, Error, Pka, ProjectivePoint},
+    Uint,
+};
 use {defmt_rtt as _, panic_probe as _};
 use cortex_m_rt::entry;
 use cortex_m::asm;
 use defmt::info;
-use core::{
-    mem::size_of,
-    ptr::{read_volatile, write_volatile},
-};
-
-const MODE: u8 = 0x2F;
-const BASE: usize = 0x520C_2000;
-const PKA_RAM_OFFSET: usize = 0x400; 
-const RAM_BASE: usize = BASE + PKA_RAM_OFFSET;
-const RAM_NUM_DW: usize = 667;
-
-// PKA RAM locations for ECC addition
-pub const MODULUS_LENGTH_OFFSET: usize = BASE + 0x408;
-
-pub const MODULUS_OFFSET_PTA: usize = BASE + 0x470;
-pub const POINT_P_X_PTA: usize = BASE + 0xD60;
-pub const POINT_P_Y_PTA: usize = BASE + 0xDB8;
-pub const POINT_P_Z_PTA: usize = BASE + 0xE10;
-pub const MONTGOMERY_PTA: usize = BASE + 0x4C8;
-
-pub const RESULT_X_PTA: usize = BASE + 0x578;
-pub const RESULT_Y_PTA: usize = BASE + 0x5D0;
-pub const RESULT_ERROR_PTA: usize = BASE + 0x680;
-
-const A_SIGN: u32 = 0x1;
-const A: [u32; 8] = [
-    0x00000000, 0x00000000, 0x00000000, 0x00000000, 
-    0x00000000, 0x00000000, 0x00000000, 0x00000003,
-];
-const N: [u32; 8] = [
-    0xffffffff, 0x00000001, 0x00000000, 0x00000000, 
-    0x00000000, 0xffffffff, 0xffffffff, 0xffffffff,
-];
-
-const B: [u32; 8] = [
-    0x5ac635d8, 0xaa3a93e7, 0xb3ebbd55, 0x769886bc,
-    0x651d06b0, 0xcc53b0f6, 0x3bce3c3e, 0x27d2604b
-];
-
-const BASE_POINT_X: [u32; 8] = [
-    0x6b17d1f2, 0xe12c4247, 0xf8bce6e5, 0x63a440f2, 
-    0x77037d81, 0x2deb33a0, 0xf4a13945, 0xd898c296,
-];
-
-const BASE_POINT_Y: [u32; 8] = [
-    0x4fe342e2, 0xfe1a7f9b, 0x8ee7eb4a, 0x7c0f9e16, 
-    0x2bce3357, 0x6b315ece, 0xcbb64068, 0x37bf51f5,
-];
-
-const PRIME_ORDER: [u32; 8] = [
-    0xffffffff, 0x00000000, 0xffffffff, 0xffffffff, 
-    0xbce6faad, 0xa7179e84, 0xf3b9cac2, 0xfc632551,
-];
-
-const X2: [u32; 8] = [
-    0x7CF27B18, 0x8D034F7E, 0x8A523803, 0x04B51AC3, 
-    0xC08969E2, 0x77F21B35, 0xA60B48FC, 0x47669978,
-];
-
-const Y2: [u32; 8] = [
-    0x07775510, 0xDB8ED040, 0x293D9AC6, 0x9F7430DB, 
-    0xBA7DADE6, 0x3CE98229, 0x9E04B79D, 0x227873D1,
-];
-
-// Projective coordiantes of BASE_POINT + BASE_POINT
-const PROJ_X: [u32; 8] = [
-    0x9A978F59, 0xACD1B5AD, 0x570E7D52, 0xDCFCDE43, 
-    0x804B4227, 0x4F61DDCF, 0x1E7D8483, 0x91D6C70F
-];
 
-const PROJ_Y: [u32; 8] = [
-    0x4126885E, 0x7F786AF9, 0x5338238, 0xE5346D5F, 
-    0xE77FC463, 0x88668BD0, 0xFD59BE31, 0x90D2F5D1
-];
-
-const PROJ_Z: [u32; 8] = [
-    0x9FC685C5, 0xFC34FF37, 0x1DCFD694, 0xF81F3C2C, 
-    0x579C66AE, 0xD662BD9D, 0x976C80D0, 0x6F7EA3EA
-];
-
-const R2MODN: [u32; 8] = [
-    0x00000002, 0x00000000, 0xFFFFFFFA, 0x00000004, 
-    0xFFFFFFFB, 0xFFFFFFFF, 0x00000008, 0xFFFFFFFC
-];
-
-const OPERAND_LENGTH: u32 = 8 * 32;
-const WORD_LENGTH: usize = (OPERAND_LENGTH as usize)/32;   
-
-
-unsafe fn write_ram(offset: usize, buf: &[u32]) {
-    debug_assert_eq!(offset % 4, 0);
-    debug_assert!(offset + buf.len() * size_of::<u32>() < 0x520C_33FF);
-    buf.iter().rev().enumerate().for_each(|(idx, &dw)| {
-        write_volatile((offset + idx * size_of::<u32>()) as *mut u32, dw)
-    });
-}
-
-unsafe fn read_ram(offset: usize, buf: &mut [u32]) {
-    debug_assert_eq!(offset % 4, 0);
-    debug_assert!(offset + buf.len() * size_of::<u32>() < 0x520C_33FF);
-    buf.iter_mut().rev().enumerate().for_each(|(idx, dw)| {
-        *dw = read_volatile((offset + idx * size_of::<u32>()) as *const u32);
-    });
-}
-
-unsafe fn zero_ram() {
-    (0..RAM_NUM_DW)
-        .into_iter()
-        .for_each(|dw| unsafe { write_volatile((dw * 4 + RAM_BASE) as *mut u32, 0) });
-}
+// 2G
+const X2: Uint<8> =
+    Uint::from_be_hex("7CF27B188D034F7E8A52380304B51AC3C08969E277F21B35A60B48FC47669978");
+const Y2: Uint<8> =
+    Uint::from_be_hex("07775510DB8ED040293D9AC69F7430DBBA7DADE63CE982299E04B79D227873D1");
+
+// Projective coordiantes of BASE_POINT + BASE_POINT, as returned by the PKA
+// complete addition
+const PROJ_X: Uint<8> =
+    Uint::from_be_hex("9A978F59ACD1B5AD570E7D52DCFCDE43804B42274F61DDCF1E7D848391D6C70F");
+const PROJ_Y: Uint<8> =
+    Uint::from_be_hex("4126885E7F786AF905338238E5346D5FE77FC46388668BD0FD59BE3190D2F5D1");
+const PROJ_Z: Uint<8> =
+    Uint::from_be_hex("9FC685C5FC34FF371DCFD694F81F3C2C579C66AED662BD9D976C80D06F7EA3EA");
 
 #[entry]
-unsafe fn main() -> ! {
+fn main() -> ! {
     let p = stm32wba55::Peripherals::take().unwrap();
-    let pka = &p.PKA;
-    let clock = &p.RCC;
+    let pka = p.PKA;
+    let rcc = &p.RCC;
     let rng = &p.RNG;
 
-    // Enable HSI as a stable clock source
-    clock.rcc_cr().modify(|_, w| w
-    .hseon().set_bit()
-    );
-    while clock.rcc_cr().read().hserdy().bit_is_clear() {
-        asm::nop();
-    }
-
-    // Enable RNG clock. Select the source clock. Select the AHB clock
-    clock.rcc_ccipr2().write(|w| w.rngsel().b_0x2());
-    clock.rcc_ahb2enr().modify(|_, w| w.rngen().set_bit());
-    while clock.rcc_ahb2enr().read().rngen().bit_is_clear() {
-        asm::nop();
-    }
-
-    // Configure RNG
-    // To configure, CONDRST bit is set to 1 in the same access and CONFIGLOCK remains at 0
-    rng.rng_cr().write(|w| w
-        .rngen().clear_bit()
-        .condrst().set_bit()
-        .configlock().clear_bit() 
-        .nistc().clear_bit()   
-        .ced().clear_bit() 
-    );
-
-    // First clear CONDRST while keeping RNGEN disabled
-    rng.rng_cr().modify(|_, w| w
-        .condrst().clear_bit()
-    );
-
-    // Then enable RNG in a separate step
-    rng.rng_cr().modify(|_, w| w
-        .rngen().set_bit()
-        .ie().set_bit()
-    );
-    
-    while rng.rng_sr().read().drdy().bit_is_clear() {
-        asm::nop();
-    }
-    info!("RNG enabled successfully");
+    let mut pka = Pka::new(pka, rcc, rng);
+    info!("PKA Initialized");
 
-    // Enable PKA peripheral clock via RCC_AHB2ENR register
-    clock.rcc_ahb2enr().modify(|_, w| w.pkaen().set_bit());
+    let curve = curve::NIST_P256;
+    let point = ProjectivePoint::new(PROJ_X, PROJ_Y, PROJ_Z);
 
-    // Reset PKA before enabling (sometimes helps with initialization)
-    pka.pka_cr().modify(|_, w| w.en().clear_bit());
-    for _ in 0..10 {
-        asm::nop();
+    match point.to_affine(&mut pka, &curve) {
+        Ok(affine) => {
+            info!("Result X: {}", affine.x);
+            info!("Result Y: {}", affine.y);
+            info!("Match 2G: {}", affine == AffinePoint::new(X2, Y2));
+        }
+        Err(e) => info!("Error during projective to affine: {:?}", e),
     }
 
-    // Enable PKA peripheral
-    pka.pka_cr().write(|w| w
-        .en().set_bit()
-        .mode().bits(MODE)
+    info!(
+        "Point at infinity rejected: {}",
+        ProjectivePoint::IDENTITY.to_affine(&mut pka, &curve) == Err(Error::PointAtInfinity)
     );
- 
-    // Wait for PKA to initialize
-    while pka.pka_sr().read().initok().bit_is_clear() {
-        asm::nop();
-    }
-    info!("PKA initialized successfully!");
 
-    // Clear any previous error flags
-    pka.pka_clrfr().write(|w| w
-        .addrerrfc().set_bit()
-        .ramerrfc().set_bit()
-        .procendfc().set_bit()
-    );
-
-
-    // Write the values - using 32-bit words
-    zero_ram();
-    // constant values for P-256 curve
-    write_ram(MODULUS_LENGTH_OFFSET, &[OPERAND_LENGTH]);
-    write_ram(MODULUS_OFFSET_PTA, &N);
-
-    write_ram(POINT_P_X_PTA, &PROJ_X);
-    write_ram(POINT_P_Y_PTA, &PROJ_Y);
-    write_ram(POINT_P_Z_PTA, &PROJ_Z);
-
-    // Check the values 
-    let mut buf = [0u32; 8];
-    read_ram(POINT_P_X_PTA, &mut buf);
-    info!("x: {:#X}", buf);
-    read_ram(POINT_P_Y_PTA, &mut buf);
-    info!("y: {:#X}", buf);
-    read_ram(POINT_P_Z_PTA, &mut buf);
-    info!("z: {:#X}", buf);
-
-    // Configure PKA operation mode and start
-    info!("Starting PKA operation...");
-    pka.pka_cr().modify(|_, w| w
-        .mode().bits(MODE)
-        .start().set_bit()
-    );
-
-    // Wait for processing to complete - PROCENDF is 1 when done
-    info!("Waiting for operation to complete...");
-    while pka.pka_sr().read().procendf().bit_is_clear() {
+    loop {
         asm::nop();
     }
-    info!("Operation complete!");
-
-    // Read the result
-    let mut result = [0u32; 1];
-    let mut result_x = [0u32; 8];
-    let mut result_y = [0u32; 8];
-    read_ram(RESULT_ERROR_PTA, &mut result);
-    if result[0] == 0xD60D {
-        info!("No errors: {:#X}", result[0]);
-        read_ram(RESULT_X_PTA, &mut result_x);
-        read_ram(RESULT_Y_PTA, &mut result_y);
-        info!("POINT (X, Y): ({:#X}, {:#X})", result_x, result_y);
-    }
-    if result[0] == 0xA3B7 {
-        info!("Error in computation: {:#X}", result);
-    }
-            
-    // Clear the completion flag
-    pka.pka_clrfr().write(|w| w.procendfc().set_bit());
-
-    loop {}
-}
\ No newline at end of file
+}
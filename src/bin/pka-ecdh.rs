@@ -0,0 +1,112 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{curve, AffinePoint, EphemeralSecret, Error, Pka},
+    Rng, Uint,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::asm;
+use defmt::info;
+
+// RFC 5903, section 8.1: ECDH with P-256
+const RFC5903_I: Uint<8> =
+    Uint::from_be_hex("C88F01F510D9AC3F70A292DAA2316DE544E9AAB8AFE84049C62A9C57862D1433");
+const RFC5903_GIX: Uint<8> =
+    Uint::from_be_hex("DAD0B65394221CF9B051E1FECA5787D098DFE637FC90B9EF945D0C3772581180");
+const RFC5903_GIY: Uint<8> =
+    Uint::from_be_hex("5271A0461CDB8252D61F1C456FA3E59AB1F45B33ACCF5F58389E0577B8990BB3");
+const RFC5903_R: Uint<8> =
+    Uint::from_be_hex("C6EF9C5D78AE012A011164ACB397CE2088685D8F06BF9BE0B283AB46476BEE53");
+const RFC5903_GRX: Uint<8> =
+    Uint::from_be_hex("D12DFB5289C8D4F81208B70270398C342296970A0BCCB74C736FC7554494BF63");
+const RFC5903_GRY: Uint<8> =
+    Uint::from_be_hex("56FBF3CA366CC23E8157854C13C58D6AAC23F046ADA30F8353E74F33039872AB");
+const RFC5903_GIRX: Uint<8> =
+    Uint::from_be_hex("D6840F6B42F6EDAFD13116E0E12565202FEF8E9ECE7DCE03812464D04B9442DE");
+
+// Wycheproof ecdh_secp256r1_ecpoint_test, tcId 1
+const WYCHEPROOF_PRIVATE: Uint<8> =
+    Uint::from_be_hex("0612465c89a023ab17855b0a6bcebfd3febb53aef84138647b5352e02c10c346");
+const WYCHEPROOF_PUBLIC_X: Uint<8> =
+    Uint::from_be_hex("62d5bd3372af75fe85a040715d0f502428e07046868b0bfdfa61d731afe44f26");
+const WYCHEPROOF_PUBLIC_Y: Uint<8> =
+    Uint::from_be_hex("ac333a93a9e70a81cd5a95b5bf8d13990eb741c8c38872b4a07d275a014e30cf");
+const WYCHEPROOF_SHARED: Uint<8> =
+    Uint::from_be_hex("53020d908b0219328b658b525f26780e3ae12bcd952bb25a93bc0895e1714285");
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let pka = p.PKA;
+    let rcc = &p.RCC;
+
+    let mut pka = Pka::new(pka, rcc, &p.RNG);
+    let mut rng = Rng::new(&p.RNG);
+    info!("PKA Initialized");
+
+    let curve = curve::NIST_P256;
+
+    // RFC 5903: both public keys and the shared secret from either side
+    let initiator = EphemeralSecret::from_scalar(&curve, RFC5903_I).unwrap();
+    let responder = EphemeralSecret::from_scalar(&curve, RFC5903_R).unwrap();
+    let gi = AffinePoint::new(RFC5903_GIX, RFC5903_GIY);
+    let gr = AffinePoint::new(RFC5903_GRX, RFC5903_GRY);
+    info!("RFC 5903 gi match: {}", initiator.public_key(&mut pka) == Ok(gi));
+    info!("RFC 5903 gr match: {}", responder.public_key(&mut pka) == Ok(gr));
+    match initiator.diffie_hellman(&mut pka, &gr) {
+        Ok(secret) => info!("RFC 5903 initiator secret match: {}", *secret.raw_secret() == RFC5903_GIRX),
+        Err(e) => info!("Error during ECDH: {:?}", e),
+    }
+    match responder.diffie_hellman(&mut pka, &gi) {
+        Ok(secret) => info!("RFC 5903 responder secret match: {}", *secret.raw_secret() == RFC5903_GIRX),
+        Err(e) => info!("Error during ECDH: {:?}", e),
+    }
+
+    // Wycheproof: a valid public key
+    let secret = EphemeralSecret::from_scalar(&curve, WYCHEPROOF_PRIVATE).unwrap();
+    let public = AffinePoint::new(WYCHEPROOF_PUBLIC_X, WYCHEPROOF_PUBLIC_Y);
+    match secret.diffie_hellman(&mut pka, &public) {
+        Ok(shared) => info!("Wycheproof tcId 1 match: {}", *shared.raw_secret() == WYCHEPROOF_SHARED),
+        Err(e) => info!("Error during ECDH: {:?}", e),
+    }
+
+    // Invalid public keys must be rejected
+    let off_curve = AffinePoint::new(public.x, public.y.add_mod(&Uint::ONE, &curve.modulus));
+    let x_too_large = AffinePoint::new(curve.modulus, public.y);
+    info!(
+        "Point at infinity rejected: {}",
+        secret.diffie_hellman(&mut pka, &AffinePoint::IDENTITY).err() == Some(Error::PointAtInfinity)
+    );
+    info!(
+        "Point off the curve rejected: {}",
+        secret.diffie_hellman(&mut pka, &off_curve).err() == Some(Error::NotOnCurve)
+    );
+    info!(
+        "Coordinate x >= p rejected: {}",
+        secret.diffie_hellman(&mut pka, &x_too_large).err() == Some(Error::OutOfRange)
+    );
+
+    // Two fresh key pairs must agree on the same secret
+    let alice = EphemeralSecret::random(&mut rng, &curve);
+    let bob = EphemeralSecret::random(&mut rng, &curve);
+    match (alice.public_key(&mut pka), bob.public_key(&mut pka)) {
+        (Ok(alice_public), Ok(bob_public)) => {
+            match (
+                alice.diffie_hellman(&mut pka, &bob_public),
+                bob.diffie_hellman(&mut pka, &alice_public),
+            ) {
+                (Ok(a), Ok(b)) => info!("Ephemeral secrets match: {}", a.raw_secret() == b.raw_secret()),
+                (a, b) => info!("Error during ECDH: {:?} {:?}", a.err(), b.err()),
+            }
+        }
+        (a, b) => info!("Error computing public keys: {:?} {:?}", a.err(), b.err()),
+    }
+
+    loop {
+        asm::nop();
+    }
+}
@@ -0,0 +1,81 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Signs a hash and exports the signature as r || s and as DER, the form
+// `openssl dgst -verify` expects, then verifies the DER signature back.
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{curve, EcdsaSignature, Pka, SigningKey},
+    Rng, Uint,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::asm;
+use defmt::info;
+
+// SHA-256("sample")
+const HASH: Uint<8> = Uint::from_be_hex(
+    "af2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf",
+);
+
+// RFC 6979, appendix A.2.5: P-256 private key
+const PRIVATE_KEY: Uint<8> = Uint::from_be_hex(
+    "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+);
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let mut pka = Pka::new(p.PKA, &p.RCC, &p.RNG);
+    let mut rng = Rng::new(&p.RNG);
+    info!("PKA Initialized");
+
+    let curve = curve::NIST_P256;
+    let signing_key = match SigningKey::from_scalar(&mut pka, &curve, PRIVATE_KEY) {
+        Ok(key) => key,
+        Err(e) => {
+            info!("Error loading the private key: {:?}", e);
+            loop {
+                asm::nop();
+            }
+        }
+    };
+    let verifying_key = signing_key.verifying_key();
+
+    match signing_key.sign(&mut pka, &mut rng, &HASH) {
+        Ok((r_sign, s_sign)) => {
+            let signature = EcdsaSignature { r_sign: &r_sign, s_sign: &s_sign };
+
+            let mut raw = [0; 64];
+            if let Ok(len) = signature.to_bytes(&curve, &mut raw) {
+                info!("r || s: {:02x}", raw[..len]);
+            }
+
+            let mut der = [0; 72];
+            match signature.to_der(&curve, &mut der) {
+                Ok(len) => {
+                    info!("DER: {:02x}", der[..len]);
+                    match verifying_key.verify_der(&mut pka, &der[..len], &HASH) {
+                        Ok(()) => info!("DER signature verified"),
+                        Err(e) => info!("Error during ECDSA verification: {:?}", e),
+                    }
+                }
+                Err(e) => info!("Error encoding the signature: {:?}", e),
+            }
+
+            // A redundant leading zero in r is valid BER but not DER
+            let non_minimal = [0x30, 0x07, 0x02, 0x02, 0x00, 0x01, 0x02, 0x01, 0x01];
+            info!(
+                "Non-minimal DER rejected: {:?}",
+                verifying_key.verify_der(&mut pka, &non_minimal, &HASH)
+            );
+        }
+        Err(e) => info!("Error during ECDSA signing: {:?}", e),
+    }
+
+    loop {
+        asm::nop();
+    }
+}
@@ -0,0 +1,155 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{
+        ed25519::{Error, Signature, SigningKey, VerifyingKey},
+        Pka,
+    },
+    Rng,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::asm;
+use defmt::info;
+
+struct TestVector {
+    secret_key: [u8; 32],
+    public_key: [u8; 32],
+    message: &'static [u8],
+    signature: [u8; 64],
+}
+
+// RFC 8032, section 7.1: TEST 1, TEST 2 and TEST 3
+const TEST_VECTORS: [TestVector; 3] = [
+    TestVector {
+        secret_key: [
+            0x9d, 0x61, 0xb1, 0x9d, 0xef, 0xfd, 0x5a, 0x60, 0xba, 0x84, 0x4a, 0xf4, 0x92, 0xec, 0x2c, 0xc4,
+            0x44, 0x49, 0xc5, 0x69, 0x7b, 0x32, 0x69, 0x19, 0x70, 0x3b, 0xac, 0x03, 0x1c, 0xae, 0x7f, 0x60,
+        ],
+        public_key: [
+            0xd7, 0x5a, 0x98, 0x01, 0x82, 0xb1, 0x0a, 0xb7, 0xd5, 0x4b, 0xfe, 0xd3, 0xc9, 0x64, 0x07, 0x3a,
+            0x0e, 0xe1, 0x72, 0xf3, 0xda, 0xa6, 0x23, 0x25, 0xaf, 0x02, 0x1a, 0x68, 0xf7, 0x07, 0x51, 0x1a,
+        ],
+        message: &[],
+        signature: [
+            0xe5, 0x56, 0x43, 0x00, 0xc3, 0x60, 0xac, 0x72, 0x90, 0x86, 0xe2, 0xcc, 0x80, 0x6e, 0x82, 0x8a,
+            0x84, 0x87, 0x7f, 0x1e, 0xb8, 0xe5, 0xd9, 0x74, 0xd8, 0x73, 0xe0, 0x65, 0x22, 0x49, 0x01, 0x55,
+            0x5f, 0xb8, 0x82, 0x15, 0x90, 0xa3, 0x3b, 0xac, 0xc6, 0x1e, 0x39, 0x70, 0x1c, 0xf9, 0xb4, 0x6b,
+            0xd2, 0x5b, 0xf5, 0xf0, 0x59, 0x5b, 0xbe, 0x24, 0x65, 0x51, 0x41, 0x43, 0x8e, 0x7a, 0x10, 0x0b,
+        ],
+    },
+    TestVector {
+        secret_key: [
+            0x4c, 0xcd, 0x08, 0x9b, 0x28, 0xff, 0x96, 0xda, 0x9d, 0xb6, 0xc3, 0x46, 0xec, 0x11, 0x4e, 0x0f,
+            0x5b, 0x8a, 0x31, 0x9f, 0x35, 0xab, 0xa6, 0x24, 0xda, 0x8c, 0xf6, 0xed, 0x4f, 0xb8, 0xa6, 0xfb,
+        ],
+        public_key: [
+            0x3d, 0x40, 0x17, 0xc3, 0xe8, 0x43, 0x89, 0x5a, 0x92, 0xb7, 0x0a, 0xa7, 0x4d, 0x1b, 0x7e, 0xbc,
+            0x9c, 0x98, 0x2c, 0xcf, 0x2e, 0xc4, 0x96, 0x8c, 0xc0, 0xcd, 0x55, 0xf1, 0x2a, 0xf4, 0x66, 0x0c,
+        ],
+        message: &[0x72],
+        signature: [
+            0x92, 0xa0, 0x09, 0xa9, 0xf0, 0xd4, 0xca, 0xb8, 0x72, 0x0e, 0x82, 0x0b, 0x5f, 0x64, 0x25, 0x40,
+            0xa2, 0xb2, 0x7b, 0x54, 0x16, 0x50, 0x3f, 0x8f, 0xb3, 0x76, 0x22, 0x23, 0xeb, 0xdb, 0x69, 0xda,
+            0x08, 0x5a, 0xc1, 0xe4, 0x3e, 0x15, 0x99, 0x6e, 0x45, 0x8f, 0x36, 0x13, 0xd0, 0xf1, 0x1d, 0x8c,
+            0x38, 0x7b, 0x2e, 0xae, 0xb4, 0x30, 0x2a, 0xee, 0xb0, 0x0d, 0x29, 0x16, 0x12, 0xbb, 0x0c, 0x00,
+        ],
+    },
+    TestVector {
+        secret_key: [
+            0xc5, 0xaa, 0x8d, 0xf4, 0x3f, 0x9f, 0x83, 0x7b, 0xed, 0xb7, 0x44, 0x2f, 0x31, 0xdc, 0xb7, 0xb1,
+            0x66, 0xd3, 0x85, 0x35, 0x07, 0x6f, 0x09, 0x4b, 0x85, 0xce, 0x3a, 0x2e, 0x0b, 0x44, 0x58, 0xf7,
+        ],
+        public_key: [
+            0xfc, 0x51, 0xcd, 0x8e, 0x62, 0x18, 0xa1, 0xa3, 0x8d, 0xa4, 0x7e, 0xd0, 0x02, 0x30, 0xf0, 0x58,
+            0x08, 0x16, 0xed, 0x13, 0xba, 0x33, 0x03, 0xac, 0x5d, 0xeb, 0x91, 0x15, 0x48, 0x90, 0x80, 0x25,
+        ],
+        message: &[0xaf, 0x82],
+        signature: [
+            0x62, 0x91, 0xd6, 0x57, 0xde, 0xec, 0x24, 0x02, 0x48, 0x27, 0xe6, 0x9c, 0x3a, 0xbe, 0x01, 0xa3,
+            0x0c, 0xe5, 0x48, 0xa2, 0x84, 0x74, 0x3a, 0x44, 0x5e, 0x36, 0x80, 0xd7, 0xdb, 0x5a, 0xc3, 0xac,
+            0x18, 0xff, 0x9b, 0x53, 0x8d, 0x16, 0xf2, 0x90, 0xae, 0x67, 0xf7, 0x60, 0x98, 0x4d, 0xc6, 0x59,
+            0x4a, 0x7c, 0x15, 0xe9, 0x71, 0x6e, 0xd2, 0x8d, 0xc0, 0x27, 0xbe, 0xce, 0xea, 0x1e, 0xc4, 0x0a,
+        ],
+    },
+];
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let pka = p.PKA;
+    let rcc = &p.RCC;
+
+    let mut pka = Pka::new(pka, rcc, &p.RNG);
+    let mut rng = Rng::new(&p.RNG);
+    info!("PKA Initialized");
+
+    for (idx, vector) in TEST_VECTORS.iter().enumerate() {
+        let signing_key = match SigningKey::from_bytes(&mut pka, &vector.secret_key) {
+            Ok(key) => key,
+            Err(e) => {
+                info!("TEST {}: error expanding the secret key: {:?}", idx + 1, e);
+                continue;
+            }
+        };
+        info!(
+            "TEST {} public key match: {}",
+            idx + 1,
+            *signing_key.verifying_key().as_bytes() == vector.public_key
+        );
+
+        match signing_key.sign(&mut pka, vector.message) {
+            Ok(signature) => info!(
+                "TEST {} signature match: {}",
+                idx + 1,
+                signature.to_bytes() == vector.signature
+            ),
+            Err(e) => info!("TEST {}: error during signing: {:?}", idx + 1, e),
+        }
+
+        let signature = Signature::from_bytes(&vector.signature);
+        match VerifyingKey::from_bytes(&mut pka, &vector.public_key) {
+            Ok(verifying_key) => {
+                info!(
+                    "TEST {} signature verified: {}",
+                    idx + 1,
+                    verifying_key.verify(&mut pka, vector.message, &signature).is_ok()
+                );
+
+                // Flipping a bit of R must make the signature invalid
+                let mut tampered = vector.signature;
+                tampered[0] ^= 0x01;
+                info!(
+                    "TEST {} tampered signature rejected: {}",
+                    idx + 1,
+                    verifying_key.verify(&mut pka, vector.message, &Signature::from_bytes(&tampered))
+                        == Err(Error::Invalid)
+                );
+            }
+            Err(e) => info!("TEST {}: error decoding the public key: {:?}", idx + 1, e),
+        }
+    }
+
+    // Round trip with a fresh key
+    match SigningKey::generate(&mut pka, &mut rng) {
+        Ok(signing_key) => {
+            let verifying_key = signing_key.verifying_key();
+            info!("Public key: {}", verifying_key);
+            match signing_key.sign(&mut pka, b"sample") {
+                Ok(signature) => info!(
+                    "Fresh key signature verified: {}",
+                    verifying_key.verify(&mut pka, b"sample", &signature).is_ok()
+                ),
+                Err(e) => info!("Error during signing: {:?}", e),
+            }
+        }
+        Err(e) => info!("Error during key generation: {:?}", e),
+    }
+
+    loop {
+        asm::nop();
+    }
+}
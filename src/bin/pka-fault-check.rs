@@ -0,0 +1,110 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Exercises the verify-after-sign check: a bit of r or s of an ECDSA signature,
+// or of an RSA CRT signature, is flipped in the PKA RAM before the driver
+// reads it. The signature must be rejected with `FaultDetected` and the PKA
+// RAM left zeroed, and the next signature must go through.
+use core::ptr::read_volatile;
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{curve, Error, Fault, Pka, RsaCrtKey, SigningKey},
+    Rng, Uint,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::asm;
+use defmt::info;
+
+const BASE: usize = 0x520C_2000;
+const PKA_RAM_OFFSET: usize = 0x400;
+const RAM_BASE: usize = BASE + PKA_RAM_OFFSET;
+const RAM_NUM_DW: usize = 667;
+
+// SHA-256("sample")
+const HASH: Uint<8> = Uint::from_be_hex(
+    "af2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf",
+);
+
+// RFC 6979, appendix A.2.5: P-256 private key
+const PRIVATE_KEY: Uint<8> = Uint::from_be_hex(
+    "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+);
+
+// RSA-1024 test key
+const MODULUS: Uint<32> = Uint::from_be_hex(
+    "ac9ae08df30eed9eb465be82af1b166782f4053ce308cd5c71f89424b67e2cb4\
+     19799abf578e227f31303f455c513c383a46118a49956a2f6d334c27e294a30d\
+     14963e929139d7e78358ffae37c5981a7f734e37b73c2a8687a326f55efb6613\
+     5712464900943858bb20eef8304fbb7379c12a26026e9b80028f5e204d8917db",
+);
+const PUBLIC_EXPONENT: u32 = 65537;
+const P: Uint<16> = Uint::from_be_hex(
+    "d64aa4c2ef4812ae88b8be004ba1f95313675289499b2bb41eec50dacbd7e2f1\
+     63cd3d4fd779be0257961c85ff2611913c5ebdfc29f436090f384af39063ddc1",
+);
+const Q: Uint<16> = Uint::from_be_hex(
+    "ce332527fdd9af6d1b3d72e4cf4bc8abf386ac0e9334b4bd6b6dc3862d91c9e6\
+     52f2cb16e2eb3680c420e5dd90b5106163037da630c8a1a3b9f25a4604eed49b",
+);
+const DP: Uint<16> = Uint::from_be_hex(
+    "409d373af40d865c8c2b30574476ed87c83502651505add2dcf422992697ccfd\
+     3e3b977e2899b3da5ef08b5f3805118cfa6812df230358fbb8541ffd7b0f31c1",
+);
+const DQ: Uint<16> = Uint::from_be_hex(
+    "58a5b650a61ac307661199e67294431661a0c13196e9aed64c6c2905f36b5ce0\
+     6f42c03afb9d6196243b7c772fb217f3b64c96e52ecde267f01617e74851a107",
+);
+const QINV: Uint<16> = Uint::from_be_hex(
+    "453dbc66e9316f4383785c66c5885df9f6f9e2881ca0aac8821628957c17d125\
+     cc5621ab73c4090466c7e0048fa37e2a21211ccfa282890db770bf49e3173ab1",
+);
+// "Sample message"
+const MESSAGE: Uint<32> = Uint::from_be_hex("53616d706c65206d657373616765");
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let mut pka = Pka::new(p.PKA, &p.RCC, &p.RNG);
+    let mut rng = Rng::new(&p.RNG);
+    info!("PKA Initialized");
+
+    let curve = curve::NIST_P256;
+    let signing_key = SigningKey::from_scalar(&mut pka, &curve, PRIVATE_KEY).unwrap();
+    let verifying_key = signing_key.verifying_key();
+    pka.set_verify_after_sign(true);
+
+    for fault in [Fault::EcdsaR, Fault::EcdsaS] {
+        pka.inject_fault(fault);
+        let result = signing_key.sign(&mut pka, &mut rng, &HASH);
+        assert!(result == Err(Error::FaultDetected));
+        assert!(ram_is_zero());
+        info!("ECDSA with {:?} corrupted: {:?}", fault, result);
+
+        let (r_sign, s_sign) = signing_key.sign(&mut pka, &mut rng, &HASH).unwrap();
+        assert!(verifying_key.verify(&mut pka, &r_sign, &s_sign, &HASH) == Ok(()));
+    }
+
+    let key = RsaCrtKey::new(MODULUS, PUBLIC_EXPONENT, P, Q, DP, DQ, QINV);
+    let signature = pka.rsa_crt_sign(&key, &MESSAGE).unwrap();
+
+    pka.inject_fault(Fault::RsaCrt);
+    let result = pka.rsa_crt_sign(&key, &MESSAGE);
+    assert!(result == Err(Error::FaultDetected));
+    assert!(ram_is_zero());
+    info!("RSA CRT corrupted: {:?}", result);
+
+    assert!(pka.rsa_crt_sign(&key, &MESSAGE) == Ok(signature));
+
+    info!("All faults detected");
+    loop {
+        asm::nop();
+    }
+}
+
+fn ram_is_zero() -> bool {
+    (0..RAM_NUM_DW * 2)
+        .all(|word| unsafe { read_volatile((word * 4 + RAM_BASE) as *const u32) } == 0)
+}
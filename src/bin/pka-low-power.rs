@@ -0,0 +1,84 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Runs the same batch of ECDSA P-256 signatures twice: first spinning on
+// PROCENDF, then sleeping in WFI until the PKA interrupt. PA12 is high during
+// each batch; trigger on it and compare the supply current of both windows.
+use stm32wba::stm32wba55::{self, interrupt};
+use stm32wba55cg::{
+    pka::{self, curve, Pka, SigningKey},
+    Rng, Uint,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::asm;
+use defmt::info;
+
+// SHA-256("sample")
+const HASH: Uint<8> = Uint::from_be_hex(
+    "af2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf",
+);
+
+const SIGNATURES: usize = 20;
+
+#[interrupt]
+fn PKA() {
+    pka::on_interrupt();
+}
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let rcc = &p.RCC;
+    let gpio = &p.GPIOA;
+
+    rcc.rcc_ahb2enr().modify(|_, w| w.gpioaen().set_bit());
+    // PA12 as push-pull output, low speed, no pull-up/pull-down, initially low
+    gpio.gpioa_moder().modify(|_, w| unsafe { w.mode12().bits(0b01) });
+    gpio.gpioa_otyper().modify(|_, w| w.ot12().clear_bit());
+    gpio.gpioa_ospeedr().modify(|_, w| unsafe { w.ospeed12().bits(0b00) });
+    gpio.gpioa_pupdr().modify(|_, w| unsafe { w.pupd12().bits(0b00) });
+    gpio.gpioa_bsrr().write(|w| w.br12().set_bit());
+
+    let mut pka = Pka::new(p.PKA, rcc, &p.RNG);
+    let mut rng = Rng::new(&p.RNG);
+    info!("PKA Initialized");
+
+    let curve = curve::NIST_P256;
+    let signing_key = match SigningKey::generate(&mut pka, &mut rng, &curve) {
+        Ok(key) => key,
+        Err(e) => {
+            info!("Error during key generation: {:?}", e);
+            loop {
+                asm::nop();
+            }
+        }
+    };
+
+    for interrupt in [false, true] {
+        if interrupt {
+            pka.enable_interrupt();
+        }
+        info!("Signing {} times, completion: {}", SIGNATURES, pka.completion());
+
+        gpio.gpioa_bsrr().write(|w| w.bs12().set_bit());
+        let mut failures = 0;
+        for _ in 0..SIGNATURES {
+            if signing_key.sign(&mut pka, &mut rng, &HASH).is_err() {
+                failures += 1;
+            }
+        }
+        gpio.gpioa_bsrr().write(|w| w.br12().set_bit());
+        info!("Done, {} failures", failures);
+
+        // Leave a gap between both windows on the scope
+        asm::delay(1_000_000);
+    }
+    pka.disable_interrupt();
+
+    loop {
+        asm::nop();
+    }
+}
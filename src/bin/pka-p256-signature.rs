@@ -0,0 +1,49 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Signs and verifies through the RustCrypto `signature` traits, the way code
+// written for the `p256` crate does.
+use core::cell::RefCell;
+
+use p256::ecdsa::Signature;
+use signature::{Signer, Verifier};
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{P256SigningKey, Pka},
+    Rng,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::asm;
+use defmt::info;
+
+// RFC 6979, appendix A.2.5: P-256 private key
+const PRIVATE_KEY: [u8; 32] = [
+    0xc9, 0xaf, 0xa9, 0xd8, 0x45, 0xba, 0x75, 0x16, 0x6b, 0x5c, 0x21, 0x57, 0x67, 0xb1, 0xd6, 0x93,
+    0x4e, 0x50, 0xc3, 0xdb, 0x36, 0xe8, 0x9b, 0x12, 0x7b, 0x8a, 0x62, 0x2b, 0x12, 0x0f, 0x67, 0x21,
+];
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let pka = RefCell::new(Pka::new(p.PKA, &p.RCC, &p.RNG));
+    info!("PKA Initialized");
+
+    let signing_key = P256SigningKey::from_bytes(&pka, Rng::new(&p.RNG), &PRIVATE_KEY).unwrap();
+    let verifying_key = signing_key.verifying_key();
+
+    let signature: Result<Signature, _> = signing_key.try_sign(b"sample");
+    match signature {
+        Ok(signature) => {
+            info!("Signature verified: {}", verifying_key.verify(b"sample", &signature).is_ok());
+            info!("Other message rejected: {}", verifying_key.verify(b"test", &signature).is_err());
+        }
+        Err(_) => info!("Error during ECDSA signing"),
+    }
+
+    loop {
+        asm::nop();
+    }
+}
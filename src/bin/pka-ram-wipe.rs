@@ -0,0 +1,83 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Reads the whole PKA RAM back after a signature through `SigningKey::sign`,
+// after one through `Pka::ecdsa_sign`, and after the driver is dropped, and
+// checks that no word of the key, the nonce or the intermediates is left.
+use core::ptr::read_volatile;
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{curve, Pka, SigningKey},
+    Rng, Uint,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::asm;
+use defmt::info;
+
+const BASE: usize = 0x520C_2000;
+const PKA_RAM_OFFSET: usize = 0x400;
+const RAM_BASE: usize = BASE + PKA_RAM_OFFSET;
+const RAM_NUM_DW: usize = 667;
+
+// SHA-256("sample")
+const HASH: Uint<8> = Uint::from_be_hex(
+    "af2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf",
+);
+
+// RFC 6979, appendix A.2.5: P-256 private key
+const PRIVATE_KEY: Uint<8> = Uint::from_be_hex(
+    "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+);
+
+// RFC 6979, appendix A.2.5: nonce for SHA-256("sample")
+const NONCE: Uint<8> = Uint::from_be_hex(
+    "a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60",
+);
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let mut pka = Pka::new(p.PKA, &p.RCC, &p.RNG);
+    let mut rng = Rng::new(&p.RNG);
+    info!("PKA Initialized");
+
+    let curve = curve::NIST_P256;
+    let signing_key = SigningKey::from_scalar(&mut pka, &curve, PRIVATE_KEY).unwrap();
+    let verifying_key = signing_key.verifying_key();
+
+    signing_key.sign(&mut pka, &mut rng, &HASH).unwrap();
+    assert!(ram_is_zero());
+    info!("PKA RAM zeroed after SigningKey::sign");
+
+    let (mut r_sign, mut s_sign) = (Uint::ZERO, Uint::ZERO);
+    pka.ecdsa_sign(
+        &curve,
+        &NONCE,
+        &PRIVATE_KEY,
+        &verifying_key.public_key(),
+        &HASH,
+        &mut r_sign,
+        &mut s_sign,
+    )
+    .unwrap();
+    assert!(ram_is_zero());
+    info!("PKA RAM zeroed after Pka::ecdsa_sign");
+
+    // Leave the operands of a verification behind, then drop the driver
+    verifying_key.verify(&mut pka, &r_sign, &s_sign, &HASH).unwrap();
+    drop(pka);
+    assert!(ram_is_zero());
+    info!("PKA RAM zeroed after dropping Pka");
+
+    loop {
+        asm::nop();
+    }
+}
+
+fn ram_is_zero() -> bool {
+    (0..RAM_NUM_DW * 2)
+        .all(|word| unsafe { read_volatile((word * 4 + RAM_BASE) as *const u32) } == 0)
+}
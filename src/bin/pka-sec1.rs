@@ -0,0 +1,57 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Exports a public key as a 33-byte compressed SEC1 point, as sent over BLE,
+// and recovers y from it with the square root on the PKA.
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    pka::{curve, Pka, SigningKey, VerifyingKey},
+    Rng,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::asm;
+use defmt::info;
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let mut pka = Pka::new(p.PKA, &p.RCC, &p.RNG);
+    let mut rng = Rng::new(&p.RNG);
+    info!("PKA Initialized");
+
+    let curve = curve::NIST_P256;
+    let signing_key = match SigningKey::generate(&mut pka, &mut rng, &curve) {
+        Ok(key) => key,
+        Err(e) => {
+            info!("Error during key generation: {:?}", e);
+            loop {
+                asm::nop();
+            }
+        }
+    };
+    let verifying_key = signing_key.verifying_key();
+
+    let mut uncompressed = [0; 65];
+    if let Ok(len) = verifying_key.to_sec1_bytes(false, &mut uncompressed) {
+        info!("Uncompressed: {:02x}", uncompressed[..len]);
+    }
+
+    let mut compressed = [0; 33];
+    match verifying_key.to_sec1_bytes(true, &mut compressed) {
+        Ok(len) => {
+            info!("Compressed: {:02x}", compressed[..len]);
+            match VerifyingKey::from_sec1_bytes(&mut pka, &curve, &compressed[..len]) {
+                Ok(decoded) => info!("Decompressed key matches: {}", decoded == verifying_key),
+                Err(e) => info!("Error during decompression: {:?}", e),
+            }
+        }
+        Err(e) => info!("Error encoding the public key: {:?}", e),
+    }
+
+    loop {
+        asm::nop();
+    }
+}
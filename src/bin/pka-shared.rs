@@ -0,0 +1,129 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Two tasks use the PKA at the same time through a SharedPka: a pairing task
+// runs ECDH P-256 key agreements while a telemetry task signs readings with
+// ECDSA P-256. Each operation holds the PKA until it is done, so both tasks
+// always get their own results: every shared secret matches the one computed
+// by the peer and every signature verifies.
+use stm32wba::stm32wba55::{self, interrupt};
+use stm32wba55cg::{
+    hash::{self, Algorithm},
+    pka::{self, curve, EllipticCurve, EphemeralSecret, Pka, SharedPka, SigningKey},
+    Hash, Rng, Uint,
+};
+use {defmt_rtt as _, panic_probe as _};
+use defmt::info;
+use embassy_executor::Spawner;
+use embassy_sync::blocking_mutex::raw::ThreadModeRawMutex;
+use embassy_time::{Duration, Timer};
+use static_cell::StaticCell;
+
+static CURVE: EllipticCurve<8, 8> = curve::NIST_P256;
+static RNG: StaticCell<stm32wba55::RNG> = StaticCell::new();
+static SHARED_PKA: StaticCell<SharedPka<ThreadModeRawMutex>> = StaticCell::new();
+
+const PAIRINGS: u32 = 5;
+const READINGS: u32 = 10;
+
+#[interrupt]
+fn PKA() {
+    pka::on_interrupt();
+}
+
+#[interrupt]
+fn HASH() {
+    hash::on_interrupt();
+}
+
+/// Pairs with a simulated peer: both sides draw a key pair, exchange public
+/// keys and must end up with the same secret.
+#[embassy_executor::task]
+async fn pairing(pka: &'static SharedPka<ThreadModeRawMutex>, rng: &'static stm32wba55::RNG) {
+    let mut rng = Rng::new(rng);
+    for round in 0..PAIRINGS {
+        let local = EphemeralSecret::random(&mut rng, &CURVE);
+        let peer = EphemeralSecret::random(&mut rng, &CURVE);
+
+        let (local_public, peer_public) =
+            match (pka.public_key(&local).await, pka.public_key(&peer).await) {
+                (Ok(local_public), Ok(peer_public)) => (local_public, peer_public),
+                (Err(e), _) | (_, Err(e)) => {
+                    info!("Pairing {}: error computing public keys: {:?}", round, e);
+                    continue;
+                }
+            };
+
+        match (
+            pka.diffie_hellman(&local, &peer_public).await,
+            pka.diffie_hellman(&peer, &local_public).await,
+        ) {
+            (Ok(local_secret), Ok(peer_secret)) => info!(
+                "Pairing {}: shared secrets match: {}",
+                round,
+                local_secret.raw_secret() == peer_secret.raw_secret()
+            ),
+            (Err(e), _) | (_, Err(e)) => info!("Pairing {}: error during ECDH: {:?}", round, e),
+        }
+        Timer::after(Duration::from_millis(20)).await;
+    }
+}
+
+/// Signs a SHA-256 digest of each reading and checks the signature.
+#[embassy_executor::task]
+async fn telemetry(
+    pka: &'static SharedPka<ThreadModeRawMutex>,
+    mut hash: Hash,
+    rng: &'static stm32wba55::RNG,
+) {
+    let mut rng = Rng::new(rng);
+    let signing_key = {
+        let mut pka = pka.lock().await;
+        match SigningKey::generate(&mut pka, &mut rng, &CURVE) {
+            Ok(key) => key,
+            Err(e) => {
+                info!("Error during key generation: {:?}", e);
+                return;
+            }
+        }
+    };
+    let verifying_key = signing_key.verifying_key();
+
+    for reading in 0..READINGS {
+        let mut digest = [0; 32];
+        hash.digest_async(Algorithm::Sha256, &reading.to_be_bytes(), &mut digest)
+            .await;
+        let hash_uint = Uint::<8>::from_be_bytes(&digest).unwrap();
+
+        match pka.sign(&signing_key, &mut rng, &hash_uint).await {
+            Ok((r_sign, s_sign)) => {
+                match pka.verify(&verifying_key, &r_sign, &s_sign, &hash_uint).await {
+                    Ok(()) => info!("Reading {}: signature verified", reading),
+                    Err(e) => info!("Reading {}: error during ECDSA verification: {:?}", reading, e),
+                }
+            }
+            Err(e) => info!("Reading {}: error during ECDSA signing: {:?}", reading, e),
+        }
+        Timer::after(Duration::from_millis(10)).await;
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) {
+    // Time driver for the timers
+    let _ = embassy_stm32::init(Default::default());
+    let p = stm32wba55::Peripherals::take().unwrap();
+
+    let mut pka = Pka::new(p.PKA, &p.RCC, &p.RNG);
+    let mut hash = Hash::new(p.HASH, &p.RCC);
+    pka.enable_interrupt();
+    hash.enable_interrupt();
+    let pka = SHARED_PKA.init(SharedPka::new(pka));
+    let rng = RNG.init(p.RNG);
+    info!("PKA and HASH initialized");
+
+    spawner.spawn(pairing(pka, rng).unwrap());
+    spawner.spawn(telemetry(pka, hash, rng).unwrap());
+}
@@ -0,0 +1,132 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Exercises the recovery path: a cycle budget far too small for an ECDSA
+// signature makes the wait time out, then the PKA is reset through RCC and
+// the same signature goes through with the default budget.
+//
+// Then injects a hung PKA: SysTick fires in the middle of a signature and
+// gates the PKA clock, so the operation never ends. The wait must still time
+// out, both polling and with the PKA interrupt enabled. The interrupt case
+// polls too: with a budget set, blocking waits do not sleep in WFI, which the
+// default `Timeout::Never` of the interrupt mode would do forever here.
+use stm32wba::stm32wba55::{self, interrupt};
+use stm32wba55cg::{
+    pka::{self, curve, Error, Pka, SigningKey},
+    Rng, Timeout, Uint,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m::{asm, peripheral::syst::SystClkSource};
+use cortex_m_rt::{entry, exception};
+use defmt::info;
+
+// SHA-256("sample")
+const HASH: Uint<8> = Uint::from_be_hex(
+    "af2bdbe1aa9b6ec1e2ade1d694f41fc71a831d0268e9891562113d8a62add1bf",
+);
+
+// RFC 6979, appendix A.2.5: P-256 private key
+const PRIVATE_KEY: Uint<8> = Uint::from_be_hex(
+    "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+);
+
+// RFC 6979, appendix A.2.5: nonce for SHA-256("sample")
+const NONCE: Uint<8> = Uint::from_be_hex(
+    "a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60",
+);
+
+// Well past the operand loading, well before the end of the signature
+const STALL_AFTER_CYCLES: u32 = 200_000;
+
+#[interrupt]
+fn PKA() {
+    pka::on_interrupt();
+}
+
+#[exception]
+fn SysTick() {
+    let mut core = unsafe { cortex_m::Peripherals::steal() };
+    core.SYST.disable_interrupt();
+    core.SYST.disable_counter();
+    // The PKA stops where it is; its registers read as zero
+    let rcc = unsafe { &*stm32wba55::RCC::ptr() };
+    rcc.rcc_ahb2enr().modify(|_, w| w.pkaen().clear_bit());
+}
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let mut core = cortex_m::Peripherals::take().unwrap();
+    let rcc = &p.RCC;
+
+    let mut pka = match Pka::try_new(p.PKA, rcc, &p.RNG) {
+        Ok(pka) => pka,
+        Err(e) => {
+            info!("PKA did not come up: {:?}", e);
+            loop {
+                asm::nop();
+            }
+        }
+    };
+    let mut rng = Rng::new(&p.RNG);
+    info!("PKA Initialized");
+
+    let curve = curve::NIST_P256;
+    let signing_key = SigningKey::from_scalar(&mut pka, &curve, PRIVATE_KEY).unwrap();
+    let verifying_key = signing_key.verifying_key();
+    let default_timeout = pka.timeout();
+
+    pka.set_timeout(Timeout::Cycles(1_000));
+    let result = signing_key.sign(&mut pka, &mut rng, &HASH);
+    assert!(result == Err(Error::Timeout));
+    info!("Signing with 1000 cycles: {:?}", result);
+
+    pka.set_timeout(default_timeout);
+    pka.reset(rcc).unwrap();
+    info!("PKA reset");
+
+    let (r_sign, s_sign) = signing_key.sign(&mut pka, &mut rng, &HASH).unwrap();
+    assert!(verifying_key.verify(&mut pka, &r_sign, &s_sign, &HASH) == Ok(()));
+    info!("Signing after reset verified");
+
+    // A budget a healthy signature fits in many times over, kept when the
+    // interrupt is enabled
+    pka.set_timeout(Timeout::Cycles(100_000_000));
+    for interrupt in [false, true] {
+        if interrupt {
+            pka.enable_interrupt();
+        }
+
+        core.SYST.set_clock_source(SystClkSource::Core);
+        core.SYST.set_reload(STALL_AFTER_CYCLES);
+        core.SYST.clear_current();
+        core.SYST.enable_interrupt();
+        core.SYST.enable_counter();
+
+        let (mut r_sign, mut s_sign) = (Uint::ZERO, Uint::ZERO);
+        let result = pka.ecdsa_sign(
+            &curve,
+            &NONCE,
+            &PRIVATE_KEY,
+            &verifying_key.public_key(),
+            &HASH,
+            &mut r_sign,
+            &mut s_sign,
+        );
+        assert!(result == Err(Error::Timeout));
+        info!("Hung PKA, completion {}: {:?}", pka.completion(), result);
+
+        rcc.rcc_ahb2enr().modify(|_, w| w.pkaen().set_bit());
+        pka.reset(rcc).unwrap();
+        let (r_sign, s_sign) = signing_key.sign(&mut pka, &mut rng, &HASH).unwrap();
+        assert!(verifying_key.verify(&mut pka, &r_sign, &s_sign, &HASH) == Ok(()));
+        info!("Signing after reset verified");
+    }
+    pka.disable_interrupt();
+
+    loop {
+        asm::nop();
+    }
+}
@@ -0,0 +1,134 @@
+#![no_std]
+#![no_main]
+
+// Reference Manual: file:///C:/Users/elopezpe/OneDrive/Documentos/PhD/micro/stm32eba55cg/rm0493-multiprotocol-wireless-bluetooth-low-energy-and-ieee802154-stm32wba5xxx-arm-based-32-bit-mcus-stmicroelectronics-en.pdf
+//
+// Runs the HASH and AES drivers through the RustCrypto `digest`, `cipher` and
+// `aead` traits and compares them with the `sha2` crate and published test
+// vectors.
+use core::cell::RefCell;
+
+use aead::AeadInPlace;
+use cipher::{BlockDecrypt, BlockEncrypt};
+use digest::{FixedOutput, Update};
+use sha2::Digest;
+use stm32wba::stm32wba55;
+use stm32wba55cg::{
+    aes::{Aes128, Aes128Gcm},
+    hash::{Sha256, SharedSha256},
+    Aes, Hash,
+};
+use {defmt_rtt as _, panic_probe as _};
+use cortex_m_rt::entry;
+use cortex_m::asm;
+use defmt::info;
+
+const MESSAGE: &[u8] = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+// Exactly one block
+const BLOCK_MESSAGE: &[u8] = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmno";
+
+// FIPS 197, appendix C.1
+const AES_KEY: [u8; 16] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f,
+];
+const AES_PLAINTEXT: [u8; 16] = [
+    0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff,
+];
+const AES_CIPHERTEXT: [u8; 16] = [
+    0x69, 0xc4, 0xe0, 0xd8, 0x6a, 0x7b, 0x04, 0x30, 0xd8, 0xcd, 0xb7, 0x80, 0x70, 0xb4, 0xc5, 0x5a,
+];
+
+// The Galois/Counter Mode of Operation (McGrew, Viega), test case 4: the
+// payload ends with a partial block
+const GCM_KEY: [u8; 16] = [
+    0xfe, 0xff, 0xe9, 0x92, 0x86, 0x65, 0x73, 0x1c, 0x6d, 0x6a, 0x8f, 0x94, 0x67, 0x30, 0x83, 0x08,
+];
+const GCM_NONCE: [u8; 12] = [
+    0xca, 0xfe, 0xba, 0xbe, 0xfa, 0xce, 0xdb, 0xad, 0xde, 0xca, 0xf8, 0x88,
+];
+const GCM_AAD: [u8; 20] = [
+    0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe, 0xef, 0xfe, 0xed, 0xfa, 0xce, 0xde, 0xad, 0xbe, 0xef,
+    0xab, 0xad, 0xda, 0xd2,
+];
+const GCM_PLAINTEXT: [u8; 60] = [
+    0xd9, 0x31, 0x32, 0x25, 0xf8, 0x84, 0x06, 0xe5, 0xa5, 0x59, 0x09, 0xc5, 0xaf, 0xf5, 0x26, 0x9a,
+    0x86, 0xa7, 0xa9, 0x53, 0x15, 0x34, 0xf7, 0xda, 0x2e, 0x4c, 0x30, 0x3d, 0x8a, 0x31, 0x8a, 0x72,
+    0x1c, 0x3c, 0x0c, 0x95, 0x95, 0x68, 0x09, 0x53, 0x2f, 0xcf, 0x0e, 0x24, 0x49, 0xa6, 0xb5, 0x25,
+    0xb1, 0x6a, 0xed, 0xf5, 0xaa, 0x0d, 0xe6, 0x57, 0xba, 0x63, 0x7b, 0x39,
+];
+const GCM_CIPHERTEXT: [u8; 60] = [
+    0x42, 0x83, 0x1e, 0xc2, 0x21, 0x77, 0x74, 0x24, 0x4b, 0x72, 0x21, 0xb7, 0x84, 0xd0, 0xd4, 0x9c,
+    0xe3, 0xaa, 0x21, 0x2f, 0x2c, 0x02, 0xa4, 0xe0, 0x35, 0xc1, 0x7e, 0x23, 0x29, 0xac, 0xa1, 0x2e,
+    0x21, 0xd5, 0x14, 0xb2, 0x54, 0x66, 0x93, 0x1c, 0x7d, 0x8f, 0x6a, 0x5a, 0xac, 0x84, 0xaa, 0x05,
+    0x1b, 0xa3, 0x0b, 0x39, 0x6a, 0x0a, 0xac, 0x97, 0x3d, 0x58, 0xe0, 0x91,
+];
+const GCM_TAG: [u8; 16] = [
+    0x5b, 0xc9, 0x4f, 0xbc, 0x32, 0x21, 0xa5, 0xdb, 0x94, 0xfa, 0xe9, 0x5a, 0xe7, 0x12, 0x1a, 0x47,
+];
+
+#[entry]
+fn main() -> ! {
+    let p = stm32wba55::Peripherals::take().unwrap();
+    let mut hash = Hash::new(p.HASH, &p.RCC);
+    let aes = RefCell::new(Aes::new(p.AES, &p.RCC));
+
+    // SHA-256 against the software implementation
+    let hardware = Sha256::new(&mut hash).chain(MESSAGE).finalize_fixed();
+    let software = sha2::Sha256::digest(MESSAGE);
+    info!("SHA-256: {:02x}", hardware.as_slice());
+    info!("SHA-256 matches sha2: {}", hardware == software);
+
+    // Empty, one block and more than two blocks
+    let mut long = [0; 200];
+    long.iter_mut().enumerate().for_each(|(idx, byte)| *byte = idx as u8);
+    let messages: [&[u8]; 3] = [b"", BLOCK_MESSAGE, &long];
+    for message in messages {
+        let hardware = Sha256::new(&mut hash).chain(message).finalize_fixed();
+        let software = sha2::Sha256::digest(message);
+        info!("SHA-256 of {} bytes matches sha2: {}", message.len(), hardware == software);
+    }
+
+    // digest::Digest, on the driver lent to the shared hashers
+    hash.share();
+    for message in messages {
+        let hardware = SharedSha256::digest(message);
+        let software = sha2::Sha256::digest(message);
+        info!("Digest of {} bytes matches sha2: {}", message.len(), hardware == software);
+    }
+    let hardware = SharedSha256::new()
+        .chain_update(&long[..100])
+        .chain_update(&long[100..])
+        .finalize();
+    info!("Digest in two parts matches sha2: {}", hardware == sha2::Sha256::digest(long));
+    info!("Driver given back: {}", Hash::unshare().is_some());
+
+    // AES-128 block cipher
+    let cipher = Aes128::new(&aes, &AES_KEY);
+    let mut block = AES_PLAINTEXT.into();
+    cipher.encrypt_block(&mut block);
+    info!("AES-128 encrypt matches: {}", block.as_slice() == AES_CIPHERTEXT);
+    cipher.decrypt_block(&mut block);
+    info!("AES-128 decrypt matches: {}", block.as_slice() == AES_PLAINTEXT);
+
+    // AES-128-GCM
+    let gcm = Aes128Gcm::new(&aes, &GCM_KEY);
+    let mut buffer = GCM_PLAINTEXT;
+    match gcm.encrypt_in_place_detached(&GCM_NONCE.into(), &GCM_AAD, &mut buffer) {
+        Ok(tag) => {
+            info!("GCM ciphertext matches: {}", buffer == GCM_CIPHERTEXT);
+            info!("GCM tag matches: {}", tag.as_slice() == GCM_TAG);
+            let opened = gcm.decrypt_in_place_detached(&GCM_NONCE.into(), &GCM_AAD, &mut buffer, &tag);
+            info!("GCM decrypt ok: {}", opened.is_ok() && buffer == GCM_PLAINTEXT);
+        }
+        Err(_) => info!("Error during GCM encryption"),
+    }
+    let mut buffer = GCM_CIPHERTEXT;
+    let mut tag = GCM_TAG;
+    tag[0] ^= 1;
+    let rejected = gcm.decrypt_in_place_detached(&GCM_NONCE.into(), &GCM_AAD, &mut buffer, &tag.into());
+    info!("GCM forged tag rejected: {}", rejected.is_err());
+
+    loop {
+        asm::nop();
+    }
+}
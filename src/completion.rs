@@ -0,0 +1,182 @@
+//! Waiting for a peripheral to finish, by polling or on its interrupt.
+//!
+//! Each driver has an `on_interrupt` function for the application's handler
+//! of the matching interrupt. It masks the peripheral interrupt enables, so
+//! the line drops while the status flags stay set, and wakes the task
+//! waiting on the driver.
+
+use core::{future::poll_fn, task::Poll};
+
+use cortex_m::{asm, interrupt, peripheral::DWT};
+use embassy_sync::waitqueue::AtomicWaker;
+
+/// How a driver waits for its peripheral to finish an operation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Completion {
+    /// Spin on the status flags.
+    Polling,
+    /// Wait for the peripheral interrupt: blocking calls sleep in WFI if
+    /// the wait is not bounded by a [`Timeout`], and async calls yield to the
+    /// executor. The application must call the driver's `on_interrupt` from
+    /// the interrupt handler.
+    Interrupt,
+}
+
+/// Budget for a wait, after which the driver gives up with a timeout error.
+///
+/// The budget is checked whenever the driver looks at the status flags.
+/// Blocking waits with a budget poll even with [`Completion::Interrupt`]:
+/// nothing would wake the core from WFI if the interrupt never came, and the
+/// cycle counter stops while it sleeps. Only [`Timeout::Duration`] also
+/// bounds async waits on the interrupt, through an embassy-time alarm.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Timeout {
+    /// Wait as long as it takes.
+    Never,
+    /// CPU cycles, counted with the DWT cycle counter.
+    Cycles(u32),
+    /// Time measured with embassy-time; the application must provide a time
+    /// driver.
+    #[cfg(feature = "time")]
+    Duration(embassy_time::Duration),
+}
+
+/// A [`Timeout`] started at some point.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Deadline {
+    Never,
+    Cycles { start: u32, budget: u32 },
+    #[cfg(feature = "time")]
+    Instant(embassy_time::Instant),
+}
+
+impl Deadline {
+    pub(crate) fn start(timeout: Timeout) -> Self {
+        match timeout {
+            Timeout::Never => Deadline::Never,
+            Timeout::Cycles(budget) => {
+                // Only sets enable bits, so other users of the DWT are not disturbed
+                let mut core = unsafe { cortex_m::Peripherals::steal() };
+                core.DCB.enable_trace();
+                core.DWT.enable_cycle_counter();
+                Deadline::Cycles {
+                    start: DWT::cycle_count(),
+                    budget,
+                }
+            }
+            #[cfg(feature = "time")]
+            Timeout::Duration(duration) => {
+                Deadline::Instant(embassy_time::Instant::now() + duration)
+            }
+        }
+    }
+
+    pub(crate) fn expired(&self) -> bool {
+        match *self {
+            Deadline::Never => false,
+            Deadline::Cycles { start, budget } => {
+                DWT::cycle_count().wrapping_sub(start) >= budget
+            }
+            #[cfg(feature = "time")]
+            Deadline::Instant(at) => embassy_time::Instant::now() >= at,
+        }
+    }
+}
+
+/// Blocks until `done` returns `true`.
+///
+/// With [`Completion::Interrupt`], `listen` enables the peripheral interrupt
+/// and the core sleeps in WFI. The check and WFI run with interrupts masked,
+/// so an interrupt raised just before WFI still wakes the core.
+pub(crate) fn block_until(
+    completion: Completion,
+    done: impl FnMut() -> bool,
+    listen: impl FnMut(),
+) {
+    block_until_deadline(completion, Deadline::Never, done, listen);
+}
+
+/// Like [`block_until`], but gives up once `deadline` has expired; returns
+/// `false` then. A deadline other than [`Deadline::Never`] is waited for by
+/// polling.
+pub(crate) fn block_until_deadline(
+    completion: Completion,
+    deadline: Deadline,
+    mut done: impl FnMut() -> bool,
+    mut listen: impl FnMut(),
+) -> bool {
+    // A hung peripheral never raises its interrupt to wake the core
+    let completion = match deadline {
+        Deadline::Never => completion,
+        _ => Completion::Polling,
+    };
+    loop {
+        let finished = match completion {
+            Completion::Polling => done(),
+            Completion::Interrupt => interrupt::free(|_| {
+                if done() {
+                    return true;
+                }
+                listen();
+                asm::wfi();
+                false
+            }),
+        };
+        if finished {
+            return true;
+        }
+        if deadline.expired() {
+            return done();
+        }
+    }
+}
+
+/// Yields until `done` returns `true`, woken through `waker`.
+///
+/// The waker is registered before `done` is checked and `listen` enables
+/// the interrupt after it, so a completion in between is never lost. With
+/// [`Completion::Polling`] the interrupt is masked in the NVIC and this
+/// blocks like [`block_until`].
+pub(crate) async fn wait_until(
+    completion: Completion,
+    waker: &AtomicWaker,
+    done: impl FnMut() -> bool,
+    listen: impl FnMut(),
+) {
+    wait_until_deadline(completion, waker, Deadline::Never, done, listen).await;
+}
+
+/// Like [`wait_until`], but gives up once `deadline` has expired; returns
+/// `false` then.
+pub(crate) async fn wait_until_deadline(
+    completion: Completion,
+    waker: &AtomicWaker,
+    deadline: Deadline,
+    mut done: impl FnMut() -> bool,
+    mut listen: impl FnMut(),
+) -> bool {
+    if completion == Completion::Polling {
+        return block_until_deadline(completion, deadline, done, listen);
+    }
+    let wait = poll_fn(|cx| {
+        waker.register(cx.waker());
+        if done() {
+            Poll::Ready(true)
+        } else if deadline.expired() {
+            Poll::Ready(false)
+        } else {
+            listen();
+            Poll::Pending
+        }
+    });
+    match deadline {
+        // An alarm wakes the task even if the interrupt never comes
+        #[cfg(feature = "time")]
+        Deadline::Instant(at) => {
+            embassy_time::with_deadline(at, wait).await.unwrap_or(false)
+        }
+        _ => wait.await,
+    }
+}
@@ -0,0 +1,287 @@
+//! HASH processor driver (SHA-1, SHA-224 and SHA-256).
+//!
+//! Data is written to `HASH_DIN` in 8-bit mode (`DATATYPE` = 0b10), so the
+//! peripheral swaps the bytes of each little-endian word back into message
+//! order. The driver buffers input so whole blocks are written at once: the
+//! core only starts on a block once the first word of the next one is in the
+//! FIFO, so the first write is one block plus one word and `DINIS` is waited
+//! for before every further block.
+//!
+//! [`Sha1`], [`Sha224`] and [`Sha256`] implement the RustCrypto `digest`
+//! traits on top of the driver.
+
+use stm32wba::stm32wba55;
+use cortex_m::{asm, peripheral::NVIC};
+use embassy_sync::waitqueue::AtomicWaker;
+
+use crate::{
+    completion::{block_until, wait_until},
+    Completion,
+};
+
+mod digest;
+
+pub use self::digest::{Sha1, Sha224, Sha256, SharedSha1, SharedSha224, SharedSha256};
+
+/// Size of a SHA-1 / SHA-2 message block in bytes.
+const BLOCK_LEN: usize = 64;
+/// Size of the first write: a block and the first word of the next one.
+const FIRST_BLOCK_LEN: usize = BLOCK_LEN + 4;
+
+static WAKER: AtomicWaker = AtomicWaker::new();
+
+/// Hash algorithm, as selected in `HASH_CR.ALGO`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Algorithm {
+    Sha1,
+    Sha224,
+    Sha256,
+}
+
+impl Algorithm {
+    const fn bits(self) -> u8 {
+        match self {
+            Algorithm::Sha1 => 0b00,
+            Algorithm::Sha224 => 0b10,
+            Algorithm::Sha256 => 0b11,
+        }
+    }
+
+    /// Digest length in bytes.
+    pub const fn output_len(self) -> usize {
+        match self {
+            Algorithm::Sha1 => 20,
+            Algorithm::Sha224 => 28,
+            Algorithm::Sha256 => 32,
+        }
+    }
+}
+
+/// Body of the `HASH` interrupt handler, for [`Completion::Interrupt`].
+///
+/// Masks the data input and digest interrupts and wakes the waiting task.
+pub fn on_interrupt() {
+    let hash = unsafe { &*stm32wba55::HASH::ptr() };
+    hash.hash_imr().modify(|_, w| {
+        w.dinie().clear_bit();
+        w.dcie().clear_bit()
+    });
+    WAKER.wake();
+}
+
+/// HASH driver.
+///
+/// One message is processed at a time: [`Hash::start`], any number of
+/// [`Hash::update`] calls, then [`Hash::finish`].
+#[derive(Debug)]
+pub struct Hash {
+    hash: stm32wba55::HASH,
+    completion: Completion,
+    algorithm: Algorithm,
+    buffer: [u8; FIRST_BLOCK_LEN],
+    buffered: usize,
+    first_block_sent: bool,
+}
+
+impl Hash {
+    pub fn new(hash: stm32wba55::HASH, rcc: &stm32wba55::RCC) -> Self {
+        // Enable HASH peripheral clock on AHB2
+        rcc.rcc_ahb2enr().modify(|_, w| w.hashen().set_bit());
+        while rcc.rcc_ahb2enr().read().hashen().bit_is_clear() {
+            asm::nop();
+        }
+
+        Self {
+            hash,
+            completion: Completion::Polling,
+            algorithm: Algorithm::Sha256,
+            buffer: [0; FIRST_BLOCK_LEN],
+            buffered: 0,
+            first_block_sent: false,
+        }
+    }
+
+    /// Waits on the HASH interrupt instead of spinning.
+    ///
+    /// Unmasks the HASH interrupt in the NVIC; the application must call
+    /// [`on_interrupt`] from its `HASH` handler.
+    pub fn enable_interrupt(&mut self) {
+        NVIC::unpend(stm32wba55::Interrupt::HASH);
+        self.completion = Completion::Interrupt;
+        unsafe { NVIC::unmask(stm32wba55::Interrupt::HASH) };
+    }
+
+    /// Masks the HASH interrupt and goes back to polling.
+    pub fn disable_interrupt(&mut self) {
+        NVIC::mask(stm32wba55::Interrupt::HASH);
+        self.completion = Completion::Polling;
+    }
+
+    /// Starts a new message, dropping any message in progress.
+    pub fn start(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+        self.buffered = 0;
+        self.first_block_sent = false;
+
+        self.hash.hash_cr().write(|w| unsafe {
+            w.algo().bits(algorithm.bits());
+            w.mode().clear_bit();
+            w.datatype().bits(0b10);
+            w.dmae().clear_bit();
+            w.init().set_bit()
+        });
+    }
+
+    /// Algorithm of the message in progress.
+    #[inline]
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    /// Appends `data` to the message.
+    pub fn update(&mut self, mut data: &[u8]) {
+        while self.fill(&mut data) {
+            if self.first_block_sent {
+                block_until(self.completion, || self.input_ready(), || self.listen_input());
+            }
+            self.write_buffer();
+        }
+    }
+
+    /// Pads the message, computes the digest and writes it to the first
+    /// [`Algorithm::output_len`] bytes of `out`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `out` is shorter than the digest.
+    pub fn finish(&mut self, out: &mut [u8]) {
+        if self.first_block_sent {
+            block_until(self.completion, || self.input_ready(), || self.listen_input());
+        }
+        self.write_last();
+        block_until(self.completion, || self.digest_ready(), || self.listen_digest());
+        self.read_digest(out);
+    }
+
+    /// Hashes `data` in one go; see [`Hash::finish`] for `out`.
+    pub fn digest(&mut self, algorithm: Algorithm, data: &[u8], out: &mut [u8]) {
+        self.start(algorithm);
+        self.update(data);
+        self.finish(out);
+    }
+
+    /// Async counterpart of [`Hash::update`].
+    pub async fn update_async(&mut self, mut data: &[u8]) {
+        while self.fill(&mut data) {
+            if self.first_block_sent {
+                wait_until(self.completion, &WAKER, || self.input_ready(), || self.listen_input())
+                    .await;
+            }
+            self.write_buffer();
+        }
+    }
+
+    /// Async counterpart of [`Hash::finish`].
+    pub async fn finish_async(&mut self, out: &mut [u8]) {
+        if self.first_block_sent {
+            wait_until(self.completion, &WAKER, || self.input_ready(), || self.listen_input())
+                .await;
+        }
+        self.write_last();
+        wait_until(self.completion, &WAKER, || self.digest_ready(), || self.listen_digest()).await;
+        self.read_digest(out);
+    }
+
+    /// Async counterpart of [`Hash::digest`].
+    pub async fn digest_async(&mut self, algorithm: Algorithm, data: &[u8], out: &mut [u8]) {
+        self.start(algorithm);
+        self.update_async(data).await;
+        self.finish_async(out).await;
+    }
+
+    /// Moves bytes from `data` into the buffer; returns `true` when the
+    /// buffer holds a full block to write.
+    fn fill(&mut self, data: &mut &[u8]) -> bool {
+        let block_len = self.block_len();
+        let take = (block_len - self.buffered).min(data.len());
+        self.buffer[self.buffered..self.buffered + take].copy_from_slice(&data[..take]);
+        self.buffered += take;
+        *data = &data[take..];
+        self.buffered == block_len
+    }
+
+    #[inline]
+    fn block_len(&self) -> usize {
+        if self.first_block_sent {
+            BLOCK_LEN
+        } else {
+            FIRST_BLOCK_LEN
+        }
+    }
+
+    /// Writes the full buffer to `HASH_DIN`.
+    fn write_buffer(&mut self) {
+        for chunk in self.buffer[..self.buffered].chunks_exact(4) {
+            let word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+            self.hash.hash_din().write(|w| unsafe { w.bits(word) });
+        }
+        self.buffered = 0;
+        self.first_block_sent = true;
+    }
+
+    /// Writes the buffered tail of the message and starts the digest.
+    fn write_last(&mut self) {
+        for chunk in self.buffer[..self.buffered].chunks(4) {
+            let mut bytes = [0; 4];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            self.hash.hash_din().write(|w| unsafe { w.bits(u32::from_le_bytes(bytes)) });
+        }
+        // Valid bits in the last word; 0 means all 32
+        let valid_bits = (self.buffered % 4 * 8) as u8;
+        self.hash.hash_str().write(|w| unsafe {
+            w.nblw().bits(valid_bits);
+            w.dcal().set_bit()
+        });
+        self.buffered = 0;
+    }
+
+    fn read_digest(&mut self, out: &mut [u8]) {
+        let out = &mut out[..self.algorithm.output_len()];
+        for (idx, chunk) in out.chunks_mut(4).enumerate() {
+            chunk.copy_from_slice(&self.hr(idx).to_be_bytes());
+        }
+    }
+
+    /// Reads `HASH_HRx`.
+    fn hr(&self, idx: usize) -> u32 {
+        match idx {
+            0 => self.hash.hash_hr0().read().bits(),
+            1 => self.hash.hash_hr1().read().bits(),
+            2 => self.hash.hash_hr2().read().bits(),
+            3 => self.hash.hash_hr3().read().bits(),
+            4 => self.hash.hash_hr4().read().bits(),
+            5 => self.hash.hash_hr5().read().bits(),
+            6 => self.hash.hash_hr6().read().bits(),
+            _ => self.hash.hash_hr7().read().bits(),
+        }
+    }
+
+    #[inline]
+    fn input_ready(&self) -> bool {
+        self.hash.hash_sr().read().dinis().bit_is_set()
+    }
+
+    #[inline]
+    fn digest_ready(&self) -> bool {
+        self.hash.hash_sr().read().dcis().bit_is_set()
+    }
+
+    fn listen_input(&self) {
+        self.hash.hash_imr().modify(|_, w| w.dinie().set_bit());
+    }
+
+    fn listen_digest(&self) {
+        self.hash.hash_imr().modify(|_, w| w.dcie().set_bit());
+    }
+}
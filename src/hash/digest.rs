@@ -0,0 +1,172 @@
+//! RustCrypto `digest` traits.
+//!
+//! [`Sha1`], [`Sha224`] and [`Sha256`] borrow the driver for one message and
+//! implement [`Update`], [`FixedOutput`], [`FixedOutputReset`] and
+//! [`Reset`], so code generic over those traits hashes on the peripheral.
+//!
+//! `digest::Digest` also needs `Default`, which a borrowed peripheral cannot
+//! provide. Once the driver is lent with [`Hash::share`], [`SharedSha1`],
+//! [`SharedSha224`] and [`SharedSha256`] take it on `default()` and give it
+//! back when dropped, so they implement `Digest`. There is a single HASH
+//! processor: only one of them can exist at a time.
+
+use core::cell::RefCell;
+
+use ::digest::{
+    consts::{U20, U28, U32},
+    FixedOutput, FixedOutputReset, HashMarker, Output, OutputSizeUser, Reset, Update,
+};
+use embassy_sync::blocking_mutex::{raw::CriticalSectionRawMutex, Mutex};
+
+use super::{Algorithm, Hash};
+
+/// Driver lent to the `Shared` hashers, while none of them holds it.
+static SHARED: Mutex<CriticalSectionRawMutex, RefCell<Option<Hash>>> =
+    Mutex::new(RefCell::new(None));
+
+impl Hash {
+    /// Lends the driver to [`SharedSha1`], [`SharedSha224`] and
+    /// [`SharedSha256`], replacing any driver lent before.
+    pub fn share(self) {
+        SHARED.lock(|shared| shared.replace(Some(self)));
+    }
+
+    /// Takes back the driver lent with [`Hash::share`].
+    ///
+    /// Returns `None` if no driver was lent, or while a hasher holds it.
+    pub fn unshare() -> Option<Self> {
+        SHARED.lock(|shared| shared.take())
+    }
+}
+
+macro_rules! hasher {
+    (
+        $(#[$doc:meta])* $name:ident, $shared:ident, $algorithm:expr, $output_size:ty
+    ) => {
+        $(#[$doc])*
+        pub struct $name<'d> {
+            hash: &'d mut Hash,
+        }
+
+        impl<'d> $name<'d> {
+            /// Starts a new message on `hash`, dropping any message in
+            /// progress.
+            pub fn new(hash: &'d mut Hash) -> Self {
+                hash.start($algorithm);
+                Self { hash }
+            }
+        }
+
+        impl HashMarker for $name<'_> {}
+
+        impl OutputSizeUser for $name<'_> {
+            type OutputSize = $output_size;
+        }
+
+        impl Update for $name<'_> {
+            fn update(&mut self, data: &[u8]) {
+                self.hash.update(data);
+            }
+        }
+
+        impl FixedOutput for $name<'_> {
+            fn finalize_into(self, out: &mut Output<Self>) {
+                self.hash.finish(out);
+            }
+        }
+
+        impl Reset for $name<'_> {
+            fn reset(&mut self) {
+                self.hash.start($algorithm);
+            }
+        }
+
+        impl FixedOutputReset for $name<'_> {
+            fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+                self.hash.finish(out);
+                self.hash.start($algorithm);
+            }
+        }
+
+        #[doc = concat!(
+            "[`", stringify!($name), "`] on the driver lent with [`Hash::share`], ",
+            "for `digest::Digest`."
+        )]
+        pub struct $shared {
+            // Only `None` once dropped
+            hash: Option<Hash>,
+        }
+
+        impl $shared {
+            fn hash(&mut self) -> &mut Hash {
+                self.hash.as_mut().unwrap()
+            }
+        }
+
+        impl Default for $shared {
+            /// Takes the shared driver and starts a new message.
+            ///
+            /// # Panics
+            ///
+            /// Panics if no driver was lent with [`Hash::share`], or if
+            /// another hasher holds it.
+            fn default() -> Self {
+                let mut hash = Hash::unshare().expect("HASH driver not shared or in use");
+                hash.start($algorithm);
+                Self { hash: Some(hash) }
+            }
+        }
+
+        impl Drop for $shared {
+            fn drop(&mut self) {
+                if let Some(hash) = self.hash.take() {
+                    hash.share();
+                }
+            }
+        }
+
+        impl HashMarker for $shared {}
+
+        impl OutputSizeUser for $shared {
+            type OutputSize = $output_size;
+        }
+
+        impl Update for $shared {
+            fn update(&mut self, data: &[u8]) {
+                self.hash().update(data);
+            }
+        }
+
+        impl FixedOutput for $shared {
+            fn finalize_into(mut self, out: &mut Output<Self>) {
+                self.hash().finish(out);
+            }
+        }
+
+        impl Reset for $shared {
+            fn reset(&mut self) {
+                self.hash().start($algorithm);
+            }
+        }
+
+        impl FixedOutputReset for $shared {
+            fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+                self.hash().finish(out);
+                self.hash().start($algorithm);
+            }
+        }
+    };
+}
+
+hasher!(
+    /// SHA-1 on the HASH processor.
+    Sha1, SharedSha1, Algorithm::Sha1, U20
+);
+hasher!(
+    /// SHA-224 on the HASH processor.
+    Sha224, SharedSha224, Algorithm::Sha224, U28
+);
+hasher!(
+    /// SHA-256 on the HASH processor.
+    Sha256, SharedSha256, Algorithm::Sha256, U32
+);
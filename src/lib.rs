@@ -0,0 +1,18 @@
+//! Drivers for the STM32WBA55 cryptographic peripherals.
+//!
+//! Reference Manual: RM0493, "Public key accelerator (PKA)", "AES hardware
+//! accelerator (AES)" and "Hash processor (HASH)".
+#![no_std]
+
+pub mod aes;
+mod completion;
+pub mod hash;
+pub mod pka;
+pub mod rng;
+pub mod uint;
+
+pub use aes::Aes;
+pub use completion::{Completion, Timeout};
+pub use hash::Hash;
+pub use rng::Rng;
+pub use uint::Uint;
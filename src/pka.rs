@@ -0,0 +1,736 @@
+//! Public key accelerator (PKA) driver.
+//!
+//! Operands are passed as [`Uint`] values; the driver copies their
+//! little-endian words into the PKA RAM, so callers never reorder words.
+//!
+//! Operations that may handle secret material (scalar multiplication, ECDSA
+//! and RSA signing and the modular arithmetic) wipe the PKA RAM once their
+//! results are read, and dropping the driver wipes it too; see [`RamErase`]
+//! for how. Signatures can also be verified before they are returned, see
+//! [`Pka::set_verify_after_sign`].
+//!
+//! ECDSA signatures convert to and from fixed-size r || s bytes and ASN.1
+//! DER, and points to and from the SEC1 encodings; see
+//! [`EcdsaSignature::to_der`] and [`AffinePoint::from_sec1_bytes`].
+
+use stm32wba::stm32wba55;
+use cortex_m::{asm, peripheral::NVIC};
+use embassy_sync::waitqueue::AtomicWaker;
+use core::{
+    mem::size_of,
+    ptr::{read_volatile, write_volatile},
+};
+
+use crate::{
+    completion::{block_until_deadline, wait_until_deadline, Deadline},
+    uint::Uint,
+    Completion, Timeout,
+};
+#[cfg(feature = "blinding")]
+use crate::rng::Rng;
+use layout::Field;
+
+mod arithmetic;
+pub mod curve;
+mod ecc;
+mod ecdh;
+mod ecdsa;
+pub mod ed25519;
+mod encoding;
+mod layout;
+mod p256;
+mod rsa;
+mod shared;
+pub mod x25519;
+
+pub use curve::{CurveError, CustomCurve, EllipticCurve, Sign};
+pub use ecc::{AffinePoint, EccMulMode, ProjectivePoint};
+pub use ecdh::{EphemeralSecret, SharedSecret};
+pub use ecdsa::{EcdsaPublicKey, EcdsaSignature, SigningKey, VerifyingKey};
+pub use p256::{P256SigningKey, P256VerifyingKey};
+pub use rsa::RsaCrtKey;
+pub use shared::{PkaGuard, SharedPka};
+
+/// Errors from PKA operations.
+///
+/// The first group comes from the `PKA_SR` flags, the second from the result
+/// code the operation writes to the PKA RAM, and the last from checks the
+/// driver runs before starting an operation. Once operands have been written,
+/// any error leaves the flags cleared and the PKA RAM zeroed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// Address access is out of range (unmapped address), `ADDRERRF`.
+    Address,
+    /// An AHB access to the PKA RAM occurred while the PKA core was computing
+    /// and using its internal RAM, `RAMERRF`.
+    Ram,
+    /// The operation in `PKA_CR.MODE` is not supported, `OPERRF`.
+    Operation,
+    /// PKA mode does not match the expected mode.
+    Mode {
+        /// Actual mode bits
+        mode: u8,
+    },
+    /// The PKA is still busy with an operation.
+    Busy,
+    /// A wait ran past its [`Timeout`]: the PKA did not come up, or an
+    /// operation did not end and was aborted.
+    Timeout,
+    /// Unknown result code.
+    Unknown {
+        /// Unknown result code bits.
+        bits: u32,
+    },
+    /// The PKA reported an error in the computation.
+    Computation,
+    /// ECDSA signing produced r = 0; retry with another nonce.
+    Rzero,
+    /// ECDSA signing produced s = 0; retry with another nonce.
+    Szero,
+    /// The ECDSA signature does not match the hash and public key.
+    InvalidSignature,
+    /// The input point is the point at infinity.
+    PointAtInfinity,
+    /// The input point does not satisfy the curve equation.
+    NotOnCurve,
+    /// A point coordinate or operand is not smaller than the modulus.
+    OutOfRange,
+    /// The scalar is 0 or not smaller than the curve order.
+    InvalidScalar,
+    /// The operand has no inverse modulo the modulus.
+    NotInvertible,
+    /// An operand is wider than its field in the PKA RAM.
+    OperandTooLong,
+    /// A signature did not pass the check run with
+    /// [`Pka::set_verify_after_sign`]: the computation was disturbed, and the
+    /// signature was discarded.
+    FaultDetected,
+    /// A signature or point encoding is malformed, or a buffer does not fit
+    /// it.
+    Encoding,
+}
+
+impl Error {
+    /// Maps the result codes shared by the operations that only report
+    /// success or a computation error.
+    const fn from_raw(raw: u32) -> Result<(), Error> {
+        match raw {
+            RESULT_OK => Ok(()),
+            RESULT_COMPUTATION => Err(Error::Computation),
+            _ => Err(Error::Unknown { bits: raw }),
+        }
+    }
+
+    const fn mode(mode: u8) -> Result<(), Error> {
+        Err(Error::Mode { mode })
+    }
+}
+
+/// Result code written by the PKA when an operation succeeds.
+const RESULT_OK: u32 = 0xD60D;
+/// Result code written by the PKA when a point or computation is rejected.
+const RESULT_ERROR: u32 = 0xA3B7;
+/// Result code written by the point check when a coordinate is not below p.
+const RESULT_OUT_OF_RANGE: u32 = 0xF946;
+/// Result code written by the PKA when the computation itself failed.
+const RESULT_COMPUTATION: u32 = 0xCBC9;
+
+/// Default budget for a polled operation: about 10 s at 100 MHz, far above
+/// the longest operation, so it only trips when the PKA hangs.
+const DEFAULT_TIMEOUT: Timeout = Timeout::Cycles(1_000_000_000);
+
+/// PKA operation codes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[repr(u8)]
+#[allow(dead_code)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub(crate) enum PkaOpcode {
+    /// Montgomery parameter computation then modular exponentiation.
+    MontgomeryParameterExponentiation = 0b000000,
+    /// Montgomery parameter computation only.
+    MontgomeryParameter = 0b000001,
+    /// Modular exponentiation only (Montgomery parameter must be loaded first).
+    ModularExponentiation = 0b000010,
+    /// Montgomery parameter computation then ECC scalar multiplication.
+    MontgomeryParameterEcc = 0b100000,
+    /// ECC scalar multiplication only (Montgomery parameter must be loaded first).
+    EccScalar = 0b100010,
+    /// ECC complete addition.
+    EccAddition = 0b100011,
+    /// ECC double base ladder.
+    EccLadder = 0b100111,
+    /// ECC projective to affine.
+    EccProjectiveAffine = 0b101111,
+    /// ECDSA signing.
+    EcdsaSign = 0b100100,
+    /// ECDSA verification.
+    EcdsaVerify = 0b100110,
+    /// Point on elliptic curve Fp check.
+    Point = 0b101000,
+    /// RSA CRT exponentiation.
+    RsaCrt = 0b000111,
+    /// Modular inversion.
+    ModularInversion = 0b001000,
+    /// Arithmetic addition.
+    ArithmeticAdd = 0b001001,
+    /// Arithmetic subtraction.
+    ArithmeticSub = 0b001010,
+    /// Arithmetic multiplication.
+    ArithmeticMul = 0b001011,
+    /// Arithmetic comparison.
+    ArithmeticCmp = 0b001100,
+    /// Modular reduction.
+    ModularRed = 0b001101,
+    /// Modular addition.
+    ModularAdd = 0b001110,
+    /// Modular subtraction.
+    ModularSub = 0b001111,
+    /// Montgomery multiplication.
+    MontgomeryMul = 0b010000,
+}
+
+impl From<PkaOpcode> for u8 {
+    fn from(x: PkaOpcode) -> Self {
+        x as u8
+    }
+}
+
+pub(crate) const BASE: usize = 0x520C_2000;
+const PKA_RAM_OFFSET: usize = 0x400;
+const RAM_BASE: usize = BASE + PKA_RAM_OFFSET;
+const RAM_NUM_DW: usize = 667;
+
+static WAKER: AtomicWaker = AtomicWaker::new();
+
+/// Body of the `PKA` interrupt handler, for [`Completion::Interrupt`].
+///
+/// Masks the end of operation and error interrupts so the
+/// line drops, and wakes the task awaiting the operation. The flags stay set
+/// in `PKA_SR` for the driver, and the next operation enables the interrupts
+/// again.
+pub fn on_interrupt() {
+    let pka = unsafe { &*stm32wba55::PKA::ptr() };
+    pka.pka_cr().modify(|_, w| {
+        w.procendie().clear_bit();
+        w.ramerrie().clear_bit();
+        w.addrerrie().clear_bit();
+        w.operrie().clear_bit()
+    });
+    WAKER.wake();
+}
+
+/// How the driver wipes the PKA RAM.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RamErase {
+    /// Write zero to every word of the RAM from the CPU.
+    Software,
+    /// Disable and enable the PKA: the hardware erases its RAM when enabled
+    /// and sets `INITOK` once done, without CPU writes. Falls back to
+    /// [`RamErase::Software`] if `INITOK` does not come within the timeout.
+    Hardware,
+}
+
+/// Signature output corrupted by [`Pka::inject_fault`].
+#[doc(hidden)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Fault {
+    /// r of an ECDSA signature.
+    EcdsaR,
+    /// s of an ECDSA signature.
+    EcdsaS,
+    /// Result of the RSA CRT exponentiation.
+    RsaCrt,
+}
+
+/// PKA driver.
+///
+/// The PKA RAM is wiped when the driver is dropped; an operation still
+/// running is stopped first.
+#[derive(Debug)]
+pub struct Pka {
+    pka: stm32wba55::PKA,
+    completion: Completion,
+    /// Set by [`Pka::set_timeout`]; the default depends on the completion.
+    timeout: Option<Timeout>,
+    ram_erase: RamErase,
+    verify_after_sign: bool,
+    fault: Option<Fault>,
+    fault_armed: bool,
+}
+
+impl Pka {
+    /// Sets up the clocks and RNG, and enables the PKA.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a clock or the PKA does not come up; see [`Pka::try_new`].
+    pub fn new(pka: stm32wba55::PKA, rcc: &stm32wba55::RCC, rng: &stm32wba55::RNG) -> Self {
+        Self::try_new(pka, rcc, rng).expect("PKA initialisation timed out")
+    }
+
+    /// Like [`Pka::new`], but returns [`Error::Timeout`] if the HSE, the RNG
+    /// or the PKA is not ready within the default timeout.
+    pub fn try_new(
+        pka: stm32wba55::PKA,
+        rcc: &stm32wba55::RCC,
+        rng: &stm32wba55::RNG,
+    ) -> Result<Self, Error> {
+        // Enable HSE (External High-Speed Clock) as a stable clock source
+        rcc.rcc_cr().modify(|_, w| w.hseon().set_bit());
+        spin_until(DEFAULT_TIMEOUT, || rcc.rcc_cr().read().hserdy().bit_is_set())?;
+
+        // Configure RNG clock
+        rcc.rcc_ccipr2().write(|w| w.rngsel().b_0x2());
+
+        // Enable RNG clock on AHB2
+        rcc.rcc_ahb2enr().modify(|_, w| w.rngen().set_bit());
+        while rcc.rcc_ahb2enr().read().rngen().bit_is_clear() {
+            asm::nop();
+        }
+
+        // Configure RNG
+        rng.rng_cr().write(|w| w
+            .rngen().clear_bit()
+            .condrst().set_bit()
+            .configlock().clear_bit()
+            .nistc().clear_bit()
+            .ced().clear_bit()
+        );
+
+        // Clear CONDRST while keeping RNGEN disabled
+        rng.rng_cr().modify(|_, w| w.condrst().clear_bit());
+
+        // Enable RNG with interrupts
+        rng.rng_cr().modify(|_, w| w
+            .rngen().set_bit()
+            .ie().set_bit()
+        );
+
+        spin_until(DEFAULT_TIMEOUT, || rng.rng_sr().read().drdy().bit_is_set())?;
+
+        // Enable PKA peripheral clock
+        rcc.rcc_ahb2enr().modify(|_, w| w.pkaen().set_bit());
+
+        let mut pka = Self {
+            pka,
+            completion: Completion::Polling,
+            timeout: None,
+            ram_erase: RamErase::Software,
+            verify_after_sign: false,
+            fault: None,
+            fault_armed: false,
+        };
+        pka.enable()?;
+        Ok(pka)
+    }
+
+    /// Resets the PKA through RCC and enables it again.
+    ///
+    /// This is the way out when an operation ended in [`Error::Timeout`] and
+    /// the PKA stays busy. The reset clears the PKA registers and RAM; the
+    /// completion mode and timeout are kept.
+    pub fn reset(&mut self, rcc: &stm32wba55::RCC) -> Result<(), Error> {
+        rcc.rcc_ahb2rstr().modify(|_, w| w.pkarst().set_bit());
+        rcc.rcc_ahb2rstr().modify(|_, w| w.pkarst().clear_bit());
+        self.enable()
+    }
+
+    /// Disables then enables the PKA and waits for `INITOK`.
+    fn enable(&mut self) -> Result<(), Error> {
+        // Reset PKA before enabling (sometimes helps with initialization)
+        self.pka.pka_cr().modify(|_, w| w.en().clear_bit());
+        for _ in 0..10 {
+            asm::nop();
+        }
+
+        // Enable PKA peripheral
+        self.pka.pka_cr().modify(|_, w| w.en().set_bit());
+
+        // Wait for PKA to initialize; INITOK raises no interrupt, so this is
+        // always polled and always bounded
+        let timeout = self.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        spin_until(timeout, || self.pka.pka_sr().read().initok().bit_is_set())
+    }
+
+    /// Sets how long operations may take before they are aborted with
+    /// [`Error::Timeout`].
+    ///
+    /// The default is 10<sup>9</sup> cycles when polling, and
+    /// [`Timeout::Never`] after [`Pka::enable_interrupt`], so that blocking
+    /// calls sleep in WFI.
+    pub fn set_timeout(&mut self, timeout: Timeout) {
+        self.timeout = Some(timeout);
+    }
+
+    /// Returns the operation timeout.
+    #[inline]
+    pub fn timeout(&self) -> Timeout {
+        match (self.timeout, self.completion) {
+            (Some(timeout), _) => timeout,
+            (None, Completion::Polling) => DEFAULT_TIMEOUT,
+            (None, Completion::Interrupt) => Timeout::Never,
+        }
+    }
+
+    /// Sets how the PKA RAM is wiped. The default is [`RamErase::Software`].
+    pub fn set_ram_erase(&mut self, ram_erase: RamErase) {
+        self.ram_erase = ram_erase;
+    }
+
+    /// Returns how the PKA RAM is wiped.
+    #[inline]
+    pub fn ram_erase(&self) -> RamErase {
+        self.ram_erase
+    }
+
+    /// Checks every ECDSA and RSA CRT signature with the matching
+    /// verification before returning it. Off by default.
+    ///
+    /// A single faulty signature can leak the private key, so a signature
+    /// that does not verify is wiped along with the PKA RAM, and the
+    /// operation fails with [`Error::FaultDetected`].
+    pub fn set_verify_after_sign(&mut self, verify_after_sign: bool) {
+        self.verify_after_sign = verify_after_sign;
+    }
+
+    /// Returns `true` if signatures are verified before they are returned.
+    #[inline]
+    pub fn verify_after_sign(&self) -> bool {
+        self.verify_after_sign
+    }
+
+    /// Flips the least significant bit of `fault` in the PKA RAM the next
+    /// time a signature computes it, after the operation ends and before the
+    /// result is read, as a glitch on the PKA would.
+    ///
+    /// Only meant to exercise [`Pka::set_verify_after_sign`]. With the
+    /// `blinding` feature an ECDSA fault hits the first result read for the
+    /// blinded r or s instead.
+    #[doc(hidden)]
+    pub fn inject_fault(&mut self, fault: Fault) {
+        self.fault = Some(fault);
+    }
+
+    /// Arms the fault injected by [`Pka::inject_fault`] if it targets
+    /// `output`, so the next result read corrupts its field first.
+    fn fault_point(&mut self, output: Fault) {
+        if self.fault == Some(output) {
+            self.fault = None;
+            self.fault_armed = true;
+        }
+    }
+
+    /// Waits for operations on the PKA interrupt: blocking calls sleep in WFI
+    /// and async calls yield instead of spinning.
+    ///
+    /// Blocking calls only sleep with [`Timeout::Never`], the default once
+    /// the interrupt is enabled; with a budget set by [`Pka::set_timeout`]
+    /// they poll, so that a PKA that never finishes still times out.
+    ///
+    /// Unmasks the PKA interrupt in the NVIC; the application must call
+    /// [`on_interrupt`] from its `PKA` handler.
+    pub fn enable_interrupt(&mut self) {
+        NVIC::unpend(stm32wba55::Interrupt::PKA);
+        self.completion = Completion::Interrupt;
+        unsafe { NVIC::unmask(stm32wba55::Interrupt::PKA) };
+    }
+
+    /// Masks the PKA interrupt and goes back to polling.
+    pub fn disable_interrupt(&mut self) {
+        NVIC::mask(stm32wba55::Interrupt::PKA);
+        self.completion = Completion::Polling;
+    }
+
+    /// Returns how operations are waited for.
+    #[inline]
+    pub fn completion(&self) -> Completion {
+        self.completion
+    }
+
+    /// RNG for the blinding countermeasures, set up by [`Pka::new`].
+    #[cfg(feature = "blinding")]
+    fn rng(&self) -> Rng<'static> {
+        Rng::new(unsafe { &*stm32wba55::RNG::ptr() })
+    }
+
+    /// Returns `true` if the PKA is enabled.
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.pka.pka_cr().read().en().bit_is_set()
+    }
+
+    #[inline]
+    fn clear_all_flags(&mut self) {
+        self.pka.pka_clrfr().write(|w| {
+            w.addrerrfc().set_bit();
+            w.ramerrfc().set_bit();
+            w.operrfc().set_bit();
+            w.procendfc().set_bit()
+        });
+    }
+
+    /// Leaves the PKA in a defined state after a failed operation: flags
+    /// cleared and RAM zeroed.
+    fn fail<T>(&mut self, error: Error) -> Result<T, Error> {
+        self.clear_all_flags();
+        self.zero_ram();
+        Err(error)
+    }
+
+    /// Aborts an operation that timed out by disabling the PKA, then
+    /// enables it again.
+    ///
+    /// Always fails with [`Error::Timeout`]. If the PKA is still busy the RAM
+    /// cannot be cleared, and only [`Pka::reset`] brings it back.
+    fn abort<T>(&mut self) -> Result<T, Error> {
+        let _ = self.enable();
+        if self.pka.pka_sr().read().busy().bit_is_set() {
+            Err(Error::Timeout)
+        } else {
+            self.fail(Error::Timeout)
+        }
+    }
+
+    /// Reads the result code in `field` and maps it with `codes`.
+    fn result_code(
+        &mut self,
+        field: Field,
+        codes: fn(u32) -> Result<(), Error>,
+    ) -> Result<(), Error> {
+        let raw = unsafe { read_volatile(field.address() as *const u32) };
+        match codes(raw) {
+            Ok(()) => Ok(()),
+            Err(e) => self.fail(e),
+        }
+    }
+
+    fn zero_ram(&mut self) {
+        if self.ram_erase == RamErase::Hardware && self.enable().is_ok() {
+            return;
+        }
+        (0..RAM_NUM_DW * 2)
+            .for_each(|word| unsafe { write_volatile((word * 4 + RAM_BASE) as *mut u32, 0) });
+    }
+
+    /// Writes `words` to `field`, least significant word first, then zeroes
+    /// the rest of the last doubleword and the doubleword after it, which
+    /// the PKA expects after each operand.
+    ///
+    /// Returns [`Error::OperandTooLong`] if `words` does not fit in the field.
+    fn write_field(&mut self, field: Field, words: &[u32]) -> Result<(), Error> {
+        if words.len() > field.max_words() {
+            return self.fail(Error::OperandTooLong);
+        }
+        let padded = words.len().div_ceil(2) * 2 + 2;
+        (0..padded).for_each(|idx| {
+            let address = field.address() + idx * size_of::<u32>();
+            let word = words.get(idx).copied().unwrap_or(0);
+            // The field lies in the PKA RAM and `padded` words fit in it
+            unsafe { write_volatile(address as *mut u32, word) }
+        });
+        Ok(())
+    }
+
+    /// Fills `buf` from `field`, least significant word first; words past the
+    /// end of the field are left untouched.
+    fn read_field(&mut self, field: Field, buf: &mut [u32]) {
+        let words = buf.len().min(field.max_words());
+        buf[..words].iter_mut().enumerate().for_each(|(idx, word)| {
+            let address = field.address() + idx * size_of::<u32>();
+            // The field lies in the PKA RAM and holds `words` words
+            *word = unsafe { read_volatile(address as *const u32) };
+        });
+    }
+
+    /// Writes a 32-bit parameter such as an operand length.
+    #[inline]
+    fn write_word(&mut self, field: Field, value: u32) {
+        // Every field holds at least one word
+        unsafe { write_volatile(field.address() as *mut u32, value) };
+    }
+
+    #[inline]
+    fn write_uint<const LIMBS: usize>(
+        &mut self,
+        field: Field,
+        value: &Uint<LIMBS>,
+    ) -> Result<(), Error> {
+        self.write_field(field, value.as_le_words())
+    }
+
+    /// Reads a `bits` long result then wipes the PKA RAM, for operations
+    /// that may have handled secret material.
+    fn take_uint<const LIMBS: usize>(&mut self, field: Field, bits: u32) -> Uint<LIMBS> {
+        let value = self.read_uint(field, bits);
+        self.zero_ram();
+        value
+    }
+
+    /// Reads a `bits` long result; the words above it are left at zero,
+    /// whatever the PKA left in RAM past the result.
+    #[inline]
+    fn read_uint<const LIMBS: usize>(&mut self, field: Field, bits: u32) -> Uint<LIMBS> {
+        if self.fault_armed {
+            self.fault_armed = false;
+            let address = field.address() as *mut u32;
+            // Every field holds at least one word
+            unsafe { write_volatile(address, read_volatile(address) ^ 1) };
+        }
+        let words = (bits as usize).div_ceil(32).min(LIMBS);
+        let mut value = Uint::ZERO;
+        self.read_field(field, &mut value.as_le_words_mut()[..words]);
+        value
+    }
+
+    #[inline]
+    unsafe fn start_process(&mut self, opcode: PkaOpcode) {
+        let interrupt = self.completion == Completion::Interrupt;
+        self.pka.pka_cr().write(|w| {
+            w.addrerrie().bit(interrupt);
+            w.ramerrie().bit(interrupt);
+            w.operrie().bit(interrupt);
+            w.procendie().bit(interrupt);
+            w.mode().bits(opcode.into());
+            w.start().set_bit();
+            w.en().set_bit()
+        });
+    }
+
+    /// Returns the opcode of the operation currently programmed in `PKA_CR`.
+    #[inline]
+    fn mode(&self) -> u8 {
+        self.pka.pka_cr().read().mode().bits()
+    }
+
+    /// Returns `true` once the current operation has ended, or raised an
+    /// error flag.
+    #[inline]
+    fn is_done(&self) -> bool {
+        let sr = self.pka.pka_sr().read();
+        sr.procendf().bit_is_set()
+            || sr.ramerrf().bit_is_set()
+            || sr.addrerrf().bit_is_set()
+            || sr.operrf().bit_is_set()
+    }
+
+    /// Waits until the current operation sets `PROCENDF`.
+    ///
+    /// With [`Completion::Interrupt`] and [`Timeout::Never`] the core sleeps
+    /// in WFI and also wakes up on an error; the interrupts were enabled when
+    /// the operation started. An operation still running after
+    /// [`Pka::timeout`] is aborted with [`Error::Timeout`].
+    fn wait_for_completion(&mut self) -> Result<(), Error> {
+        let deadline = Deadline::start(self.timeout());
+        if block_until_deadline(self.completion, deadline, || self.is_done(), || {}) {
+            Ok(())
+        } else {
+            self.abort()
+        }
+    }
+
+    /// Async counterpart of [`Pka::wait_for_completion`], woken by
+    /// [`on_interrupt`].
+    async fn wait_for_completion_async(&mut self) -> Result<(), Error> {
+        let deadline = Deadline::start(self.timeout());
+        if wait_until_deadline(self.completion, &WAKER, deadline, || self.is_done(), || {}).await {
+            Ok(())
+        } else {
+            self.abort()
+        }
+    }
+    /// Checks the flags raised while loading operands, then starts `opcode`.
+    ///
+    /// Returns [`Error::Busy`] if the PKA is still running, e.g. stuck after a
+    /// timeout.
+    fn start(&mut self, opcode: PkaOpcode) -> Result<(), Error> {
+        if self.pka.pka_sr().read().busy().bit_is_set() {
+            return Err(Error::Busy);
+        }
+        self.status()?;
+        unsafe {
+            self.start_process(opcode);
+        }
+        Ok(())
+    }
+
+    /// Waits for `opcode` to complete and checks the status flags.
+    ///
+    /// On success the flags are cleared and the output fields can be read.
+    fn finish(&mut self, opcode: PkaOpcode) -> Result<(), Error> {
+        let mode = self.mode();
+        if mode != opcode as u8 {
+            return Error::mode(mode);
+        }
+        self.wait_for_completion()?;
+        self.check_flags()
+    }
+
+    /// Async counterpart of [`Pka::finish`].
+    ///
+    /// Dropping the future does not stop the PKA: the operation runs to its
+    /// end.
+    async fn finish_async(&mut self, opcode: PkaOpcode) -> Result<(), Error> {
+        let mode = self.mode();
+        if mode != opcode as u8 {
+            return Error::mode(mode);
+        }
+        self.wait_for_completion_async().await?;
+        self.check_flags()
+    }
+
+    /// Lets an operation left running by a dropped future come to its end and
+    /// clears its flags, so the next operation starts on an idle PKA.
+    async fn settle_async(&mut self) {
+        if self.pka.pka_sr().read().busy().bit_is_set() {
+            // A timeout has already aborted the operation, and the next one
+            // reports the PKA as busy if it is stuck
+            let _ = self.wait_for_completion_async().await;
+        }
+        self.clear_all_flags();
+    }
+
+    /// Checks the error flags of a completed operation and clears all flags.
+    fn check_flags(&mut self) -> Result<(), Error> {
+        self.status()?;
+        self.clear_all_flags();
+        Ok(())
+    }
+
+    /// Maps the error flags of `PKA_SR`; on an error the flags are cleared
+    /// and the RAM zeroed.
+    fn status(&mut self) -> Result<(), Error> {
+        let sr = self.pka.pka_sr().read();
+        if sr.addrerrf().bit_is_set() {
+            self.fail(Error::Address)
+        } else if sr.ramerrf().bit_is_set() {
+            self.fail(Error::Ram)
+        } else if sr.operrf().bit_is_set() {
+            self.fail(Error::Operation)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Drop for Pka {
+    fn drop(&mut self) {
+        if self.pka.pka_sr().read().busy().bit_is_set() {
+            // Disabling the PKA stops the operation
+            let _ = self.enable();
+        }
+        self.clear_all_flags();
+        self.zero_ram();
+    }
+}
+
+/// Spins until `ready` returns `true`, or fails with [`Error::Timeout`].
+fn spin_until(timeout: Timeout, ready: impl FnMut() -> bool) -> Result<(), Error> {
+    if block_until_deadline(Completion::Polling, Deadline::start(timeout), ready, || {}) {
+        Ok(())
+    } else {
+        Err(Error::Timeout)
+    }
+}
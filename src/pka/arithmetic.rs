@@ -0,0 +1,226 @@
+//! Modular arithmetic on the PKA.
+//!
+//! The operand length written to the PKA is the bit length of the modulus, so
+//! operands must be reduced (smaller than the modulus) unless noted otherwise.
+//! Operands may be secret, e.g. Ed25519 scalars, so every operation wipes the
+//! PKA RAM once its result is read.
+
+use super::{
+    layout::{mod_exp, mod_inv, mod_op, mod_red},
+    Error, Pka, PkaOpcode,
+};
+use crate::uint::Uint;
+#[cfg(feature = "blinding")]
+use zeroize::Zeroizing;
+
+impl Pka {
+    /// Computes `(a + b) mod modulus`.
+    pub fn mod_add<const LIMBS: usize>(
+        &mut self,
+        a: &Uint<LIMBS>,
+        b: &Uint<LIMBS>,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        self.mod_op(PkaOpcode::ModularAdd, a, b, modulus)
+    }
+
+    /// Computes `(a - b) mod modulus`.
+    pub fn mod_sub<const LIMBS: usize>(
+        &mut self,
+        a: &Uint<LIMBS>,
+        b: &Uint<LIMBS>,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        self.mod_op(PkaOpcode::ModularSub, a, b, modulus)
+    }
+
+    /// Computes the Montgomery product a * b * R<sup>-1</sup> mod `modulus`.
+    pub fn montgomery_mul<const LIMBS: usize>(
+        &mut self,
+        a: &Uint<LIMBS>,
+        b: &Uint<LIMBS>,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        self.mod_op(PkaOpcode::MontgomeryMul, a, b, modulus)
+    }
+
+    /// Computes `a * b mod modulus` for an odd modulus.
+    ///
+    /// Runs two Montgomery multiplications, the second one by
+    /// R<sup>2</sup> mod `modulus` to cancel the R<sup>-1</sup> factor.
+    pub fn mod_mul<const LIMBS: usize>(
+        &mut self,
+        a: &Uint<LIMBS>,
+        b: &Uint<LIMBS>,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        let montgomery = self.montgomery_parameter(modulus)?;
+        let product = self.montgomery_mul(a, b, modulus)?;
+        self.montgomery_mul(&product, &montgomery, modulus)
+    }
+
+    /// Computes a<sup>-1</sup> mod `modulus`.
+    ///
+    /// Returns [`Error::NotInvertible`] if `a` is 0.
+    pub fn mod_inv<const LIMBS: usize>(
+        &mut self,
+        a: &Uint<LIMBS>,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        if *a >= *modulus {
+            return Err(Error::OutOfRange);
+        }
+        if a.is_zero() {
+            return Err(Error::NotInvertible);
+        }
+        self.clear_all_flags();
+        self.zero_ram();
+
+        self.write_word(mod_inv::LEN, modulus.bits() as u32);
+        self.write_uint(mod_inv::A, a)?;
+        self.write_uint(mod_inv::MODULUS, modulus)?;
+
+        self.start(PkaOpcode::ModularInversion)?;
+        self.finish(PkaOpcode::ModularInversion)?;
+        Ok(self.take_uint(mod_inv::OUT, modulus.bits() as u32))
+    }
+
+    /// Reduces `a` modulo `modulus`; `a` may be wider than the modulus,
+    /// e.g. a hash output reduced modulo a group order.
+    pub fn mod_red<const OPERAND_LIMBS: usize, const LIMBS: usize>(
+        &mut self,
+        a: &Uint<OPERAND_LIMBS>,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        self.clear_all_flags();
+        self.zero_ram();
+
+        self.write_word(mod_red::OPERAND_LEN, Uint::<OPERAND_LIMBS>::BITS as u32);
+        self.write_word(mod_red::MODULUS_LEN, modulus.bits() as u32);
+        self.write_uint(mod_red::A, a)?;
+        self.write_uint(mod_red::MODULUS, modulus)?;
+
+        self.start(PkaOpcode::ModularRed)?;
+        self.finish(PkaOpcode::ModularRed)?;
+        Ok(self.take_uint(mod_red::OUT, modulus.bits() as u32))
+    }
+
+    /// Computes base<sup>exponent</sup> mod `modulus` for an odd modulus.
+    ///
+    /// The Montgomery parameter is computed by the same PKA operation.
+    pub fn mod_exp<const LIMBS: usize, const EXP_LIMBS: usize>(
+        &mut self,
+        base: &Uint<LIMBS>,
+        exponent: &Uint<EXP_LIMBS>,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        if *base >= *modulus {
+            return Err(Error::OutOfRange);
+        }
+        if exponent.is_zero() {
+            return Ok(Uint::ONE);
+        }
+        self.clear_all_flags();
+        self.zero_ram();
+
+        self.write_word(mod_exp::EXPONENT_LEN, exponent.bits() as u32);
+        self.write_word(mod_exp::OPERAND_LEN, modulus.bits() as u32);
+        self.write_uint(mod_exp::BASE, base)?;
+        self.write_uint(mod_exp::EXPONENT, exponent)?;
+        self.write_uint(mod_exp::MODULUS, modulus)?;
+
+        self.start(PkaOpcode::MontgomeryParameterExponentiation)?;
+        self.finish(PkaOpcode::MontgomeryParameterExponentiation)?;
+        Ok(self.take_uint(mod_exp::OUT, modulus.bits() as u32))
+    }
+
+    /// Computes message<sup>private_exponent</sup> mod `modulus`, the RSA
+    /// private key operation (decryption or signature).
+    ///
+    /// With the `blinding` feature the exponentiation runs on
+    /// message * r<sup>e</sup> for a fresh random r, and the result is
+    /// multiplied by r<sup>-1</sup>, so the exponent is never applied to a
+    /// value the caller chose.
+    ///
+    /// With [`Pka::set_verify_after_sign`] the result is raised to
+    /// `public_exponent` and compared with `message` before it is returned.
+    pub fn rsa_private<const LIMBS: usize, const EXP_LIMBS: usize>(
+        &mut self,
+        message: &Uint<LIMBS>,
+        private_exponent: &Uint<EXP_LIMBS>,
+        public_exponent: u32,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        #[cfg(not(feature = "blinding"))]
+        {
+            let mut result = self.mod_exp(message, private_exponent, modulus)?;
+            if self.verify_after_sign {
+                self.check_rsa_signature(&mut result, message, public_exponent, modulus)?;
+            }
+            Ok(result)
+        }
+
+        #[cfg(feature = "blinding")]
+        {
+            if *message >= *modulus {
+                return Err(Error::OutOfRange);
+            }
+            let (blind, unblind) = self.rsa_blinding(public_exponent, modulus)?;
+            let blinded = Zeroizing::new(self.mod_mul(message, &blind, modulus)?);
+            let result = Zeroizing::new(self.mod_exp(&blinded, private_exponent, modulus)?);
+            let mut result = self.mod_mul(&result, &unblind, modulus)?;
+            if self.verify_after_sign {
+                self.check_rsa_signature(&mut result, message, public_exponent, modulus)?;
+            }
+            Ok(result)
+        }
+    }
+
+    /// Draws a random r invertible mod `modulus` and returns
+    /// (r<sup>e</sup>, r<sup>-1</sup>) mod `modulus`, to blind an RSA private
+    /// key operation.
+    #[cfg(feature = "blinding")]
+    pub(super) fn rsa_blinding<const LIMBS: usize>(
+        &mut self,
+        public_exponent: u32,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<(Zeroizing<Uint<LIMBS>>, Zeroizing<Uint<LIMBS>>), Error> {
+        let mut rng = self.rng();
+        let (blind, unblind) = loop {
+            let blind = Zeroizing::new(Uint::random_nonzero(&mut rng, modulus));
+            match self.mod_inv(&blind, modulus) {
+                // r shares a factor with the modulus
+                Err(Error::NotInvertible) => continue,
+                unblind => break (blind, Zeroizing::new(unblind?)),
+            }
+        };
+        let exponent = Uint::<1>::from_u32(public_exponent);
+        let blind = Zeroizing::new(self.mod_exp(&blind, &exponent, modulus)?);
+        Ok((blind, unblind))
+    }
+
+    /// Runs one of the two-operand modular operations that share the
+    /// A / B / modulus layout.
+    fn mod_op<const LIMBS: usize>(
+        &mut self,
+        opcode: PkaOpcode,
+        a: &Uint<LIMBS>,
+        b: &Uint<LIMBS>,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        if *a >= *modulus || *b >= *modulus {
+            return Err(Error::OutOfRange);
+        }
+        self.clear_all_flags();
+        self.zero_ram();
+
+        self.write_word(mod_op::LEN, modulus.bits() as u32);
+        self.write_uint(mod_op::A, a)?;
+        self.write_uint(mod_op::B, b)?;
+        self.write_uint(mod_op::MODULUS, modulus)?;
+
+        self.start(opcode)?;
+        self.finish(opcode)?;
+        Ok(self.take_uint(mod_op::OUT, modulus.bits() as u32))
+    }
+}
@@ -0,0 +1,253 @@
+//! Elliptic curve parameters and pre-defined curves.
+
+use crate::uint::Uint;
+
+/// Sign bit for ECDSA coefficient signing and verification.
+#[repr(u32)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Sign {
+    /// Positive.
+    Pos = 0,
+    /// Negative.
+    Neg = 1,
+}
+
+impl From<Sign> for u32 {
+    fn from(s: Sign) -> Self {
+        s as u32
+    }
+}
+
+/// Elliptic curve.
+///
+/// Used to ECDSA signing and verification and ECC scalar multiplication.
+/// The PKA length fields are taken from the bit lengths of `modulus` and
+/// `prime_order`, so the arrays may be wider than the values; see
+/// [`CustomCurve`] for curves loaded at runtime.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EllipticCurve<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> {
+    /// Curve coefficient a sign.
+    ///
+    /// **Note:** 0 for positive, 1 for negative.
+    pub coef_sign: Sign,
+    /// Curve coefficient |a|.
+    ///
+    /// **Note:** Absolute value, |a| < p.
+    pub coef_a: Uint<MODULUS_SIZE>,
+    /// Curve coefficient b.
+    ///
+    /// **Note:** b < p.
+    pub coef_b: Uint<MODULUS_SIZE>,
+    /// Curve modulus value p.
+    ///
+    /// **Note:** Odd integer prime, 0 < p < 2<sup>640</sup>
+    pub modulus: Uint<MODULUS_SIZE>,
+    /// Curve base point G coordinate x.
+    ///
+    /// **Note:** x < p
+    pub base_point_x: Uint<MODULUS_SIZE>,
+    /// Curve base point G coordinate y.
+    ///
+    /// **Note:** y < p
+    pub base_point_y: Uint<MODULUS_SIZE>,
+    /// Curve prime order n.
+    ///
+    /// **Note:** Integer prime.
+    pub prime_order: Uint<PRIME_ORDER_SIZE>,
+}
+
+/// Errors from [`EllipticCurve::validate`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CurveError {
+    /// The modulus is wider than 640 bits or than its array.
+    ModulusLength,
+    /// The prime order is zero or wider than 640 bits or than its array.
+    PrimeOrderLength,
+    /// The modulus is even or smaller than 3.
+    Modulus,
+    /// |a| or b is not smaller than p.
+    Coefficient,
+    /// A base point coordinate is not smaller than p.
+    BasePointRange,
+    /// The base point does not satisfy y<sup>2</sup> = x<sup>3</sup> + ax + b.
+    BasePointNotOnCurve,
+}
+
+/// Largest operand size supported by the PKA, in bits.
+pub const MAX_OPERAND_BITS: usize = 640;
+
+/// Number of 32-bit words needed for the largest PKA operand.
+pub const MAX_OPERAND_WORDS: usize = MAX_OPERAND_BITS / 32;
+
+/// Curve with room for any modulus and order the PKA supports, for curve
+/// parameters only known at runtime (test curves, curves from a key blob).
+///
+/// Build it with [`EllipticCurve::from_be_bytes`]; it is accepted by every
+/// operation that takes an [`EllipticCurve`].
+pub type CustomCurve = EllipticCurve<MAX_OPERAND_WORDS, MAX_OPERAND_WORDS>;
+
+impl<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>
+    EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+    /// Builds a curve from big-endian byte strings and [`validate`]s it.
+    ///
+    /// Leading zero bytes are accepted. A modulus or prime order that does
+    /// not fit its array is rejected with the matching length error; a
+    /// coefficient or base point coordinate that does not fit cannot be
+    /// smaller than p, and is rejected with [`CurveError::Coefficient`] or
+    /// [`CurveError::BasePointRange`].
+    ///
+    /// [`validate`]: EllipticCurve::validate
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_be_bytes(
+        coef_sign: Sign,
+        coef_a: &[u8],
+        coef_b: &[u8],
+        modulus: &[u8],
+        base_point_x: &[u8],
+        base_point_y: &[u8],
+        prime_order: &[u8],
+    ) -> Result<Self, CurveError> {
+        let modulus = parse(modulus, CurveError::ModulusLength)?;
+        let curve = Self {
+            coef_sign,
+            coef_a: parse(coef_a, CurveError::Coefficient)?,
+            coef_b: parse(coef_b, CurveError::Coefficient)?,
+            modulus,
+            base_point_x: parse(base_point_x, CurveError::BasePointRange)?,
+            base_point_y: parse(base_point_y, CurveError::BasePointRange)?,
+            prime_order: parse(prime_order, CurveError::PrimeOrderLength)?,
+        };
+        curve.validate()?;
+        Ok(curve)
+    }
+
+    /// Checks the curve parameters in software.
+    ///
+    /// Run it once at startup for curves that are not defined in this module;
+    /// the PKA does not check them and silently computes garbage on a bad
+    /// curve.
+    pub fn validate(&self) -> Result<(), CurveError> {
+        if self.modulus.bits() > MAX_OPERAND_BITS {
+            return Err(CurveError::ModulusLength);
+        }
+        if self.prime_order.bits() > MAX_OPERAND_BITS || self.prime_order.is_zero() {
+            return Err(CurveError::PrimeOrderLength);
+        }
+        if !self.modulus.bit(0) || self.modulus.bits() < 2 {
+            return Err(CurveError::Modulus);
+        }
+        if self.coef_a >= self.modulus || self.coef_b >= self.modulus {
+            return Err(CurveError::Coefficient);
+        }
+        if self.base_point_x >= self.modulus || self.base_point_y >= self.modulus {
+            return Err(CurveError::BasePointRange);
+        }
+        let y2 = self.base_point_y.mul_mod(&self.base_point_y, &self.modulus);
+        if y2 != self.equation_rhs(&self.base_point_x) {
+            return Err(CurveError::BasePointNotOnCurve);
+        }
+        Ok(())
+    }
+
+    /// Coefficient a reduced into [0, p - 1].
+    pub fn coef_a_mod_p(&self) -> Uint<MODULUS_SIZE> {
+        match self.coef_sign {
+            Sign::Pos => self.coef_a,
+            Sign::Neg => self.coef_a.neg_mod(&self.modulus),
+        }
+    }
+
+    /// Computes x<sup>3</sup> + ax + b mod p in software.
+    pub(crate) fn equation_rhs(&self, x: &Uint<MODULUS_SIZE>) -> Uint<MODULUS_SIZE> {
+        let p = &self.modulus;
+        let x2_plus_a = x.mul_mod(x, p).add_mod(&self.coef_a_mod_p(), p);
+        x2_plus_a.mul_mod(x, p).add_mod(&self.coef_b, p)
+    }
+
+    /// Modulus length in bits, as written to the PKA length fields.
+    pub(crate) fn modulus_length(&self) -> u32 {
+        self.modulus.bits() as u32
+    }
+
+    /// Byte length of a field element, as in the SEC1 point encodings.
+    pub fn modulus_bytes(&self) -> usize {
+        self.modulus.bits().div_ceil(8)
+    }
+
+    /// Byte length of a scalar modulo the prime order, as in the r || s
+    /// signature encoding.
+    pub fn prime_order_bytes(&self) -> usize {
+        self.prime_order.bits().div_ceil(8)
+    }
+
+    /// Prime order length in bits, as written to the PKA length fields.
+    pub(crate) fn prime_order_length(&self) -> u32 {
+        self.prime_order.bits() as u32
+    }
+}
+
+/// Reads a big-endian parameter, or returns `error` if it does not fit.
+fn parse<const LIMBS: usize>(bytes: &[u8], error: CurveError) -> Result<Uint<LIMBS>, CurveError> {
+    Uint::from_be_bytes(strip_leading_zeros(bytes)).map_err(|_| error)
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|&byte| byte != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+/// nist P-256
+pub const NIST_P256: EllipticCurve<8, 8> = EllipticCurve {
+    coef_sign: Sign::Neg,
+    coef_a: Uint::from_be_hex("03"),
+    coef_b: Uint::from_be_hex(
+        "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+    ),
+    modulus: Uint::from_be_hex(
+        "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff",
+    ),
+    base_point_x: Uint::from_be_hex(
+        "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+    ),
+    base_point_y: Uint::from_be_hex(
+        "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+    ),
+    prime_order: Uint::from_be_hex(
+        "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+    ),
+};
+
+/// Wei25519, the short Weierstrass form of Curve25519.
+///
+/// Isomorphic to the Montgomery curve of X25519 and the twisted Edwards curve
+/// of Ed25519; the base point is the image of their base points. The group
+/// has cofactor 8, `prime_order` is the order L of the base point.
+pub const WEI25519: EllipticCurve<8, 8> = EllipticCurve {
+    coef_sign: Sign::Pos,
+    coef_a: Uint::from_be_hex(
+        "2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa984914a144",
+    ),
+    coef_b: Uint::from_be_hex(
+        "7b425ed097b425ed097b425ed097b425ed097b425ed097b4260b5e9c7710c864",
+    ),
+    modulus: Uint::from_be_hex(
+        "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed",
+    ),
+    base_point_x: Uint::from_be_hex(
+        "2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaad245a",
+    ),
+    base_point_y: Uint::from_be_hex(
+        "5f51e65e475f794b1fe122d388b72eb36dc2b28192839e4dd6163a5d81312c14",
+    ),
+    prime_order: Uint::from_be_hex(
+        "1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed",
+    ),
+};
+
+/// Offset between the x coordinate of a point on [`WEI25519`] and its
+/// Curve25519 u coordinate: x = u + A / 3, with A = 486662.
+pub const WEI25519_U_OFFSET: Uint<8> =
+    Uint::from_be_hex("2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaad2451");
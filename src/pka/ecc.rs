@@ -0,0 +1,766 @@
+//! ECC scalar multiplication, point addition and point checks.
+
+#[cfg(feature = "blinding")]
+use rand_core::RngCore;
+use zeroize::Zeroize;
+#[cfg(feature = "blinding")]
+use zeroize::Zeroizing;
+
+use super::{
+    layout::{double_ladder, ecc_add, ecc_mul, montgomery, point_check, to_affine},
+    EcdsaPublicKey, EllipticCurve, Error, Pka, PkaOpcode, RESULT_ERROR, RESULT_OUT_OF_RANGE,
+};
+use crate::uint::Uint;
+
+/// Affine curve point (x, y).
+///
+/// The point at infinity has no affine coordinates; it is represented by
+/// [`AffinePoint::IDENTITY`], i.e. (0, 0), which is not on any curve the PKA
+/// supports (b ≠ 0).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AffinePoint<const MODULUS_SIZE: usize> {
+    /// Coordinate x.
+    pub x: Uint<MODULUS_SIZE>,
+    /// Coordinate y.
+    pub y: Uint<MODULUS_SIZE>,
+}
+
+impl<const MODULUS_SIZE: usize> AffinePoint<MODULUS_SIZE> {
+    /// The point at infinity.
+    pub const IDENTITY: Self = Self {
+        x: Uint::ZERO,
+        y: Uint::ZERO,
+    };
+
+    pub const fn new(x: Uint<MODULUS_SIZE>, y: Uint<MODULUS_SIZE>) -> Self {
+        Self { x, y }
+    }
+
+    /// Base point G of `curve`.
+    pub const fn generator<const PRIME_ORDER_SIZE: usize>(
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> Self {
+        Self {
+            x: curve.base_point_x,
+            y: curve.base_point_y,
+        }
+    }
+
+    /// Returns `true` for the point at infinity.
+    pub fn is_identity(&self) -> bool {
+        self.x.is_zero() && self.y.is_zero()
+    }
+
+    /// Computes `self + other` with one complete addition and one conversion
+    /// back to affine coordinates.
+    ///
+    /// Returns [`AffinePoint::IDENTITY`] if the sum is the point at infinity.
+    pub fn add<const PRIME_ORDER_SIZE: usize>(
+        &self,
+        pka: &mut Pka,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        other: &Self,
+    ) -> Result<Self, Error> {
+        let sum = pka.ecc_add(curve, &(*self).into(), &(*other).into())?;
+        Self::from_projective(pka, curve, &sum)
+    }
+
+    /// Computes `2 * self`, see [`AffinePoint::add`].
+    pub fn double<const PRIME_ORDER_SIZE: usize>(
+        &self,
+        pka: &mut Pka,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> Result<Self, Error> {
+        let sum = pka.ecc_double(curve, &(*self).into())?;
+        Self::from_projective(pka, curve, &sum)
+    }
+
+    fn from_projective<const PRIME_ORDER_SIZE: usize>(
+        pka: &mut Pka,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        point: &ProjectivePoint<MODULUS_SIZE>,
+    ) -> Result<Self, Error> {
+        match pka.ecc_to_affine(curve, point) {
+            Err(Error::PointAtInfinity) => Ok(Self::IDENTITY),
+            result => result,
+        }
+    }
+}
+
+impl<'a, const MODULUS_SIZE: usize> From<&'a AffinePoint<MODULUS_SIZE>>
+    for EcdsaPublicKey<'a, MODULUS_SIZE>
+{
+    fn from(point: &'a AffinePoint<MODULUS_SIZE>) -> Self {
+        Self {
+            curve_pt_x: &point.x,
+            curve_pt_y: &point.y,
+        }
+    }
+}
+
+/// Scalar multiplication mode.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EccMulMode {
+    /// A single PKA scalar multiplication.
+    ///
+    /// Its timing may depend on the scalar, so use it for public scalars only
+    /// (e.g. signature verification or test vectors).
+    Fast,
+    /// Montgomery ladder over the PKA complete addition.
+    ///
+    /// Every scalar bit costs one addition and one doubling on the PKA,
+    /// independently of its value, and the ladder registers are swapped
+    /// without branching. Use it for private keys and nonces; it is roughly
+    /// two orders of magnitude slower than [`EccMulMode::Fast`].
+    ///
+    /// With the `blinding` feature the scalar and the starting point are
+    /// also randomized on every call, at the cost of 32 more ladder steps.
+    Protected,
+}
+
+/// Scalar as run through the protected ladder.
+///
+/// With the `blinding` feature this is k + r * n for a fresh random 32-bit r:
+/// the ladder ends on the same point, but its bits change on every call.
+struct LadderScalar<const PRIME_ORDER_SIZE: usize> {
+    low: Uint<PRIME_ORDER_SIZE>,
+    /// Limb above `low`, only set by the blinding.
+    high: u32,
+    /// Number of ladder steps.
+    bits: usize,
+}
+
+impl<const PRIME_ORDER_SIZE: usize> LadderScalar<PRIME_ORDER_SIZE> {
+    fn bit(&self, idx: usize) -> bool {
+        match idx.checked_sub(Uint::<PRIME_ORDER_SIZE>::BITS) {
+            None => self.low.bit(idx),
+            Some(idx) => (self.high >> idx) & 1 == 1,
+        }
+    }
+}
+
+impl<const PRIME_ORDER_SIZE: usize> Drop for LadderScalar<PRIME_ORDER_SIZE> {
+    fn drop(&mut self) {
+        self.low.zeroize();
+        self.high.zeroize();
+    }
+}
+
+/// Point in Jacobian projective coordinates (X : Y : Z), i.e.
+/// x = X / Z<sup>2</sup> and y = Y / Z<sup>3</sup>, as consumed and produced
+/// by the PKA complete addition.
+///
+/// Chains of additions can stay in this form and pay for a single inversion
+/// in [`ProjectivePoint::to_affine`]. A point has many representations, so
+/// compare affine coordinates rather than projective ones.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ProjectivePoint<const MODULUS_SIZE: usize> {
+    /// Coordinate X.
+    pub x: Uint<MODULUS_SIZE>,
+    /// Coordinate Y.
+    pub y: Uint<MODULUS_SIZE>,
+    /// Coordinate Z, 0 for the point at infinity.
+    pub z: Uint<MODULUS_SIZE>,
+}
+
+impl<const MODULUS_SIZE: usize> ProjectivePoint<MODULUS_SIZE> {
+    /// The point at infinity (1 : 1 : 0).
+    pub const IDENTITY: Self = Self {
+        x: Uint::ONE,
+        y: Uint::ONE,
+        z: Uint::ZERO,
+    };
+
+    pub const fn new(x: Uint<MODULUS_SIZE>, y: Uint<MODULUS_SIZE>, z: Uint<MODULUS_SIZE>) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Lifts an affine point to Z = 1, mapping [`AffinePoint::IDENTITY`] to
+    /// [`ProjectivePoint::IDENTITY`].
+    pub fn from_affine(point: &AffinePoint<MODULUS_SIZE>) -> Self {
+        if point.is_identity() {
+            return Self::IDENTITY;
+        }
+        Self {
+            x: point.x,
+            y: point.y,
+            z: Uint::ONE,
+        }
+    }
+
+    /// Returns `true` for the point at infinity.
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    /// Computes `self + other`, see [`Pka::ecc_add`].
+    pub fn add<const PRIME_ORDER_SIZE: usize>(
+        &self,
+        pka: &mut Pka,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        other: &Self,
+    ) -> Result<Self, Error> {
+        pka.ecc_add(curve, self, other)
+    }
+
+    /// Computes `2 * self`, see [`Pka::ecc_double`].
+    pub fn double<const PRIME_ORDER_SIZE: usize>(
+        &self,
+        pka: &mut Pka,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> Result<Self, Error> {
+        pka.ecc_double(curve, self)
+    }
+
+    /// Normalises to affine coordinates, see [`Pka::ecc_to_affine`].
+    pub fn to_affine<const PRIME_ORDER_SIZE: usize>(
+        &self,
+        pka: &mut Pka,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        pka.ecc_to_affine(curve, self)
+    }
+
+    fn conditional_swap(&mut self, other: &mut Self, choice: bool) {
+        self.x.conditional_swap(&mut other.x, choice);
+        self.y.conditional_swap(&mut other.y, choice);
+        self.z.conditional_swap(&mut other.z, choice);
+    }
+}
+
+impl<const MODULUS_SIZE: usize> From<AffinePoint<MODULUS_SIZE>> for ProjectivePoint<MODULUS_SIZE> {
+    fn from(point: AffinePoint<MODULUS_SIZE>) -> Self {
+        Self::from_affine(&point)
+    }
+}
+
+impl Pka {
+    /// Computes the Montgomery parameter R<sup>2</sup> mod `modulus`.
+    pub fn montgomery_parameter<const MODULUS_SIZE: usize>(
+        &mut self,
+        modulus: &Uint<MODULUS_SIZE>,
+    ) -> Result<Uint<MODULUS_SIZE>, Error> {
+        self.montgomery_parameter_start(modulus)?;
+        self.finish(PkaOpcode::MontgomeryParameter)?;
+        Ok(self.read_uint(montgomery::OUT, modulus.bits() as u32))
+    }
+
+    fn montgomery_parameter_start<const MODULUS_SIZE: usize>(
+        &mut self,
+        modulus: &Uint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        self.clear_all_flags();
+        self.zero_ram();
+
+        self.write_word(montgomery::P_LEN, modulus.bits() as u32);
+        self.write_uint(montgomery::P, modulus)?;
+
+        self.start(PkaOpcode::MontgomeryParameter)
+    }
+
+    /// Checks that `point` lies on `curve`.
+    ///
+    /// Returns [`Error::NotOnCurve`] if the curve equation does not hold and
+    /// [`Error::OutOfRange`] if a coordinate is not smaller than p.
+    pub fn ecc_check<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        point: &AffinePoint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        let montgomery = self.montgomery_parameter(&curve.modulus)?;
+        self.ecc_check_start(curve, point, &montgomery)?;
+        self.finish(PkaOpcode::Point)?;
+        self.ecc_check_output()
+    }
+
+    fn ecc_check_start<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        point: &AffinePoint<MODULUS_SIZE>,
+        montgomery: &Uint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        self.zero_ram();
+
+        self.write_word(point_check::P_LEN, curve.modulus_length());
+        self.write_word(point_check::A_SIGN, curve.coef_sign.into());
+        self.write_uint(point_check::A, &curve.coef_a)?;
+        self.write_uint(point_check::B, &curve.coef_b)?;
+        self.write_uint(point_check::P, &curve.modulus)?;
+        self.write_uint(point_check::X, &point.x)?;
+        self.write_uint(point_check::Y, &point.y)?;
+        self.write_uint(point_check::MONTGOMERY, montgomery)?;
+
+        self.start(PkaOpcode::Point)
+    }
+
+    fn ecc_check_output(&mut self) -> Result<(), Error> {
+        self.result_code(point_check::OUT, |raw| match raw {
+            RESULT_ERROR => Err(Error::NotOnCurve),
+            RESULT_OUT_OF_RANGE => Err(Error::OutOfRange),
+            raw => Error::from_raw(raw),
+        })
+    }
+
+    /// Full public key validation of `pub_key` against `curve`.
+    ///
+    /// Rejects the point at infinity ([`Error::PointAtInfinity`]), coordinates
+    /// not smaller than p ([`Error::OutOfRange`]) and points that do not satisfy
+    /// the curve equation ([`Error::NotOnCurve`]). There is no subgroup check:
+    /// the NIST curves have prime order, while on [`curve::WEI25519`] the
+    /// point may have a small order component.
+    ///
+    /// [`curve::WEI25519`]: super::curve::WEI25519
+    pub fn validate_public_key<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        pub_key: &EcdsaPublicKey<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        let point = Self::public_key_point(curve, pub_key)?;
+        self.ecc_check(curve, &point)
+    }
+
+    /// The range checks of [`Pka::validate_public_key`], before the point
+    /// check on the PKA.
+    fn public_key_point<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        pub_key: &EcdsaPublicKey<MODULUS_SIZE>,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        let point = AffinePoint::new(*pub_key.curve_pt_x, *pub_key.curve_pt_y);
+        if point.is_identity() {
+            return Err(Error::PointAtInfinity);
+        }
+        if point.x >= curve.modulus || point.y >= curve.modulus {
+            return Err(Error::OutOfRange);
+        }
+        Ok(point)
+    }
+
+    /// Computes `scalar * point`.
+    ///
+    /// `point` is checked with [`Pka::validate_public_key`] first, and
+    /// `scalar` must be in [1, n - 1].
+    pub fn ecc_mul<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        scalar: &Uint<PRIME_ORDER_SIZE>,
+        point: &AffinePoint<MODULUS_SIZE>,
+        mode: EccMulMode,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        if scalar.is_zero() || *scalar >= curve.prime_order {
+            return Err(Error::InvalidScalar);
+        }
+        self.validate_public_key(curve, &point.into())?;
+
+        match mode {
+            EccMulMode::Fast => {
+                self.ecc_mul_start(curve, scalar, point)?;
+                self.ecc_mul_result(curve)
+            }
+            EccMulMode::Protected => self.ecc_mul_ladder(curve, scalar, point),
+        }
+    }
+
+    /// Loads the operands and starts a single PKA scalar multiplication.
+    ///
+    /// Unlike [`Pka::ecc_mul`] this does not validate its inputs.
+    pub fn ecc_mul_start<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        scalar: &Uint<PRIME_ORDER_SIZE>,
+        point: &AffinePoint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        self.clear_all_flags();
+        self.zero_ram();
+
+        self.write_word(ecc_mul::N_LEN, curve.prime_order_length());
+        self.write_word(ecc_mul::P_LEN, curve.modulus_length());
+        self.write_word(ecc_mul::A_SIGN, curve.coef_sign.into());
+        self.write_uint(ecc_mul::A, &curve.coef_a)?;
+        self.write_uint(ecc_mul::B, &curve.coef_b)?;
+        self.write_uint(ecc_mul::P, &curve.modulus)?;
+        self.write_uint(ecc_mul::N, &curve.prime_order)?;
+        self.write_uint(ecc_mul::X, &point.x)?;
+        self.write_uint(ecc_mul::Y, &point.y)?;
+        self.write_uint(ecc_mul::K, scalar)?;
+
+        self.start(PkaOpcode::MontgomeryParameterEcc)
+    }
+
+    /// Waits for the scalar multiplication started by [`Pka::ecc_mul_start`].
+    pub fn ecc_mul_result<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        self.finish(PkaOpcode::MontgomeryParameterEcc)?;
+        self.ecc_mul_output(curve)
+    }
+
+    fn ecc_mul_output<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        self.result_code(ecc_mul::OUT_ERROR, Error::from_raw)?;
+        let point = AffinePoint {
+            x: self.read_uint(ecc_mul::OUT_X, curve.modulus_length()),
+            y: self.read_uint(ecc_mul::OUT_Y, curve.modulus_length()),
+        };
+        self.zero_ram();
+        Ok(point)
+    }
+
+    /// Computes `k * p + m * q` with the PKA double base ladder.
+    ///
+    /// Both points are checked with [`Pka::validate_public_key`] and the
+    /// scalars must lie in [1, n - 1], or [`Error::InvalidScalar`] is returned:
+    /// the ladder does not handle a zero scalar. Returns
+    /// [`Error::PointAtInfinity`] if the result is the point at infinity.
+    /// Meant for public scalars, e.g. u1 * G + u2 * Q in signature
+    /// verification.
+    pub fn double_scalar_mul<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        k: &Uint<PRIME_ORDER_SIZE>,
+        p: &AffinePoint<MODULUS_SIZE>,
+        m: &Uint<PRIME_ORDER_SIZE>,
+        q: &AffinePoint<MODULUS_SIZE>,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        if k.is_zero() || m.is_zero() || *k >= curve.prime_order || *m >= curve.prime_order {
+            return Err(Error::InvalidScalar);
+        }
+        self.validate_public_key(curve, &p.into())?;
+        self.validate_public_key(curve, &q.into())?;
+
+        self.clear_all_flags();
+        self.zero_ram();
+
+        self.write_word(double_ladder::N_LEN, curve.prime_order_length());
+        self.write_word(double_ladder::P_LEN, curve.modulus_length());
+        self.write_word(double_ladder::A_SIGN, curve.coef_sign.into());
+        self.write_uint(double_ladder::A, &curve.coef_a)?;
+        self.write_uint(double_ladder::P, &curve.modulus)?;
+        self.write_uint(double_ladder::K, k)?;
+        self.write_uint(double_ladder::M, m)?;
+        self.write_uint(double_ladder::PX, &p.x)?;
+        self.write_uint(double_ladder::PY, &p.y)?;
+        self.write_uint(double_ladder::PZ, &Uint::<MODULUS_SIZE>::ONE)?;
+        self.write_uint(double_ladder::QX, &q.x)?;
+        self.write_uint(double_ladder::QY, &q.y)?;
+        self.write_uint(double_ladder::QZ, &Uint::<MODULUS_SIZE>::ONE)?;
+
+        self.start(PkaOpcode::EccLadder)?;
+        self.finish(PkaOpcode::EccLadder)?;
+        self.result_code(double_ladder::OUT_ERROR, point_at_infinity)?;
+
+        let point = AffinePoint {
+            x: self.read_uint(double_ladder::OUT_X, curve.modulus_length()),
+            y: self.read_uint(double_ladder::OUT_Y, curve.modulus_length()),
+        };
+        self.zero_ram();
+        Ok(point)
+    }
+
+    /// Montgomery ladder: R0 = O, R1 = P, and for every bit of the ladder
+    /// scalar from the top, (R0, R1) = (2 R0, R0 + R1) or (R0 + R1, 2 R1).
+    fn ecc_mul_ladder<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        scalar: &Uint<PRIME_ORDER_SIZE>,
+        point: &AffinePoint<MODULUS_SIZE>,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        let scalar = self.ladder_scalar(curve, scalar);
+        let mut r0 = ProjectivePoint::IDENTITY;
+        let mut r1 = self.ladder_start(curve, point)?;
+
+        for idx in (0..scalar.bits).rev() {
+            let bit = scalar.bit(idx);
+            r0.conditional_swap(&mut r1, bit);
+            r1 = self.ecc_add(curve, &r0, &r1)?;
+            r0 = self.ecc_double(curve, &r0)?;
+            r0.conditional_swap(&mut r1, bit);
+        }
+
+        let result = self.ecc_to_affine(curve, &r0);
+        self.zero_ram();
+        result
+    }
+
+    /// Scalar of the protected ladder, blinded with the `blinding` feature.
+    fn ladder_scalar<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        scalar: &Uint<PRIME_ORDER_SIZE>,
+    ) -> LadderScalar<PRIME_ORDER_SIZE> {
+        #[cfg(feature = "blinding")]
+        {
+            // k + r * n < 2^32 * n, so the carry fits in one limb
+            let blind = self.rng().next_u32() as u64;
+            let mut words = [0; PRIME_ORDER_SIZE];
+            let mut carry = 0u64;
+            for (idx, word) in words.iter_mut().enumerate() {
+                let sum = scalar.as_le_words()[idx] as u64
+                    + curve.prime_order.as_le_words()[idx] as u64 * blind
+                    + carry;
+                *word = sum as u32;
+                carry = sum >> 32;
+            }
+            let scalar = LadderScalar {
+                low: Uint::from_le_words(words),
+                high: carry as u32,
+                bits: curve.prime_order.bits() + 32,
+            };
+            words.zeroize();
+            scalar
+        }
+        #[cfg(not(feature = "blinding"))]
+        LadderScalar {
+            low: *scalar,
+            high: 0,
+            bits: curve.prime_order.bits(),
+        }
+    }
+
+    /// Starting point of the protected ladder. With the `blinding` feature it
+    /// is put in random Jacobian coordinates (λ<sup>2</sup> x : λ<sup>3</sup> y
+    /// : λ), so the PKA never handles the same coordinates twice.
+    #[cfg_attr(not(feature = "blinding"), allow(unused_variables))]
+    fn ladder_start<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        point: &AffinePoint<MODULUS_SIZE>,
+    ) -> Result<ProjectivePoint<MODULUS_SIZE>, Error> {
+        #[cfg(feature = "blinding")]
+        {
+            let p = &curve.modulus;
+            let lambda = Zeroizing::new(Uint::random_nonzero(&mut self.rng(), p));
+            let lambda2 = Zeroizing::new(self.mod_mul(&lambda, &lambda, p)?);
+            let lambda3 = Zeroizing::new(self.mod_mul(&lambda2, &lambda, p)?);
+            Ok(ProjectivePoint {
+                x: self.mod_mul(&point.x, &lambda2, p)?,
+                y: self.mod_mul(&point.y, &lambda3, p)?,
+                z: *lambda,
+            })
+        }
+        #[cfg(not(feature = "blinding"))]
+        Ok(ProjectivePoint::from_affine(point))
+    }
+
+    /// Adds two projective points with the PKA complete addition.
+    ///
+    /// The addition is complete: it is correct for equal points, opposite
+    /// points and the point at infinity, without branching on the inputs.
+    pub fn ecc_add<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        p: &ProjectivePoint<MODULUS_SIZE>,
+        q: &ProjectivePoint<MODULUS_SIZE>,
+    ) -> Result<ProjectivePoint<MODULUS_SIZE>, Error> {
+        self.ecc_add_start(curve, p, q)?;
+        self.finish(PkaOpcode::EccAddition)?;
+        Ok(self.ecc_add_output(curve))
+    }
+
+    fn ecc_add_start<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        p: &ProjectivePoint<MODULUS_SIZE>,
+        q: &ProjectivePoint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        self.clear_all_flags();
+        self.zero_ram();
+
+        self.write_word(ecc_add::P_LEN, curve.modulus_length());
+        self.write_word(ecc_add::A_SIGN, curve.coef_sign.into());
+        self.write_uint(ecc_add::A, &curve.coef_a)?;
+        self.write_uint(ecc_add::B, &curve.coef_b)?;
+        self.write_uint(ecc_add::P, &curve.modulus)?;
+        self.write_uint(ecc_add::PX, &p.x)?;
+        self.write_uint(ecc_add::PY, &p.y)?;
+        self.write_uint(ecc_add::PZ, &p.z)?;
+        self.write_uint(ecc_add::QX, &q.x)?;
+        self.write_uint(ecc_add::QY, &q.y)?;
+        self.write_uint(ecc_add::QZ, &q.z)?;
+
+        self.start(PkaOpcode::EccAddition)
+    }
+
+    fn ecc_add_output<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> ProjectivePoint<MODULUS_SIZE> {
+        ProjectivePoint {
+            x: self.read_uint(ecc_add::OUT_X, curve.modulus_length()),
+            y: self.read_uint(ecc_add::OUT_Y, curve.modulus_length()),
+            z: self.read_uint(ecc_add::OUT_Z, curve.modulus_length()),
+        }
+    }
+
+    /// Doubles a projective point with the PKA complete addition.
+    pub fn ecc_double<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        point: &ProjectivePoint<MODULUS_SIZE>,
+    ) -> Result<ProjectivePoint<MODULUS_SIZE>, Error> {
+        self.ecc_add(curve, point, point)
+    }
+
+    /// Converts a projective point to affine coordinates.
+    ///
+    /// Returns [`Error::PointAtInfinity`] if Z = 0.
+    pub fn ecc_to_affine<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        point: &ProjectivePoint<MODULUS_SIZE>,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        let montgomery = self.montgomery_parameter(&curve.modulus)?;
+        self.ecc_to_affine_start(curve, point, &montgomery)?;
+        self.finish(PkaOpcode::EccProjectiveAffine)?;
+        self.ecc_to_affine_output(curve)
+    }
+
+    fn ecc_to_affine_start<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        point: &ProjectivePoint<MODULUS_SIZE>,
+        montgomery: &Uint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        self.zero_ram();
+
+        self.write_word(to_affine::P_LEN, curve.modulus_length());
+        self.write_uint(to_affine::P, &curve.modulus)?;
+        self.write_uint(to_affine::X, &point.x)?;
+        self.write_uint(to_affine::Y, &point.y)?;
+        self.write_uint(to_affine::Z, &point.z)?;
+        self.write_uint(to_affine::MONTGOMERY, montgomery)?;
+
+        self.start(PkaOpcode::EccProjectiveAffine)
+    }
+
+    fn ecc_to_affine_output<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        self.result_code(to_affine::OUT_ERROR, point_at_infinity)?;
+        Ok(AffinePoint {
+            x: self.read_uint(to_affine::OUT_X, curve.modulus_length()),
+            y: self.read_uint(to_affine::OUT_Y, curve.modulus_length()),
+        })
+    }
+}
+
+/// Result codes of the operations that fail on the point at infinity.
+const fn point_at_infinity(raw: u32) -> Result<(), Error> {
+    match raw {
+        RESULT_ERROR => Err(Error::PointAtInfinity),
+        raw => Error::from_raw(raw),
+    }
+}
+
+// Async variants of the operations above: the same checks and RAM layouts,
+// awaiting the PKA interrupt between start and result.
+impl Pka {
+    /// Async counterpart of [`Pka::montgomery_parameter`].
+    pub async fn montgomery_parameter_async<const MODULUS_SIZE: usize>(
+        &mut self,
+        modulus: &Uint<MODULUS_SIZE>,
+    ) -> Result<Uint<MODULUS_SIZE>, Error> {
+        self.montgomery_parameter_start(modulus)?;
+        self.finish_async(PkaOpcode::MontgomeryParameter).await?;
+        Ok(self.read_uint(montgomery::OUT, modulus.bits() as u32))
+    }
+
+    /// Async counterpart of [`Pka::ecc_check`].
+    pub async fn ecc_check_async<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        point: &AffinePoint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        let montgomery = self.montgomery_parameter_async(&curve.modulus).await?;
+        self.ecc_check_start(curve, point, &montgomery)?;
+        self.finish_async(PkaOpcode::Point).await?;
+        self.ecc_check_output()
+    }
+
+    /// Async counterpart of [`Pka::validate_public_key`].
+    pub async fn validate_public_key_async<
+        const MODULUS_SIZE: usize,
+        const PRIME_ORDER_SIZE: usize,
+    >(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        pub_key: &EcdsaPublicKey<'_, MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        let point = Self::public_key_point(curve, pub_key)?;
+        self.ecc_check_async(curve, &point).await
+    }
+
+    /// Async counterpart of [`Pka::ecc_mul`].
+    ///
+    /// In [`EccMulMode::Protected`] every step of the ladder is awaited, so
+    /// other tasks run between the point additions; the few modular
+    /// multiplications that randomize the starting point with the `blinding`
+    /// feature are not.
+    pub async fn ecc_mul_async<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        scalar: &Uint<PRIME_ORDER_SIZE>,
+        point: &AffinePoint<MODULUS_SIZE>,
+        mode: EccMulMode,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        if scalar.is_zero() || *scalar >= curve.prime_order {
+            return Err(Error::InvalidScalar);
+        }
+        self.validate_public_key_async(curve, &point.into()).await?;
+
+        match mode {
+            EccMulMode::Fast => {
+                self.ecc_mul_start(curve, scalar, point)?;
+                self.finish_async(PkaOpcode::MontgomeryParameterEcc).await?;
+                self.ecc_mul_output(curve)
+            }
+            EccMulMode::Protected => {
+                let scalar = self.ladder_scalar(curve, scalar);
+                let mut r0 = ProjectivePoint::IDENTITY;
+                let mut r1 = self.ladder_start(curve, point)?;
+
+                for idx in (0..scalar.bits).rev() {
+                    let bit = scalar.bit(idx);
+                    r0.conditional_swap(&mut r1, bit);
+                    r1 = self.ecc_add_async(curve, &r0, &r1).await?;
+                    r0 = self.ecc_add_async(curve, &r0, &r0).await?;
+                    r0.conditional_swap(&mut r1, bit);
+                }
+
+                let result = self.ecc_to_affine_async(curve, &r0).await;
+                self.zero_ram();
+                result
+            }
+        }
+    }
+
+    /// Async counterpart of [`Pka::ecc_add`].
+    pub async fn ecc_add_async<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        p: &ProjectivePoint<MODULUS_SIZE>,
+        q: &ProjectivePoint<MODULUS_SIZE>,
+    ) -> Result<ProjectivePoint<MODULUS_SIZE>, Error> {
+        self.ecc_add_start(curve, p, q)?;
+        self.finish_async(PkaOpcode::EccAddition).await?;
+        Ok(self.ecc_add_output(curve))
+    }
+
+    /// Async counterpart of [`Pka::ecc_to_affine`].
+    pub async fn ecc_to_affine_async<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        point: &ProjectivePoint<MODULUS_SIZE>,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        let montgomery = self.montgomery_parameter_async(&curve.modulus).await?;
+        self.ecc_to_affine_start(curve, point, &montgomery)?;
+        self.finish_async(PkaOpcode::EccProjectiveAffine).await?;
+        self.ecc_to_affine_output(curve)
+    }
+}
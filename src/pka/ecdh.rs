@@ -0,0 +1,149 @@
+//! Elliptic curve Diffie-Hellman key agreement.
+
+use rand_core::{CryptoRng, RngCore};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::{AffinePoint, EccMulMode, EllipticCurve, Error, Pka};
+use crate::uint::{self, Uint};
+
+/// Ephemeral ECDH private key.
+///
+/// The scalar never leaves this type and is wiped when it is dropped. All
+/// multiplications with it run in [`EccMulMode::Protected`].
+pub struct EphemeralSecret<'c, const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> {
+    curve: &'c EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    scalar: Uint<PRIME_ORDER_SIZE>,
+}
+
+impl<'c, const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>
+    EphemeralSecret<'c, MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+    /// Draws a fresh private key in [1, n - 1].
+    pub fn random<R: RngCore + CryptoRng>(
+        rng: &mut R,
+        curve: &'c EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> Self {
+        Self {
+            curve,
+            scalar: Uint::random_nonzero(rng, &curve.prime_order),
+        }
+    }
+
+    /// Uses a known private key, e.g. to run test vectors.
+    ///
+    /// Returns [`Error::InvalidScalar`] unless `scalar` is in [1, n - 1].
+    pub fn from_scalar(
+        curve: &'c EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        scalar: Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<Self, Error> {
+        if scalar.is_zero() || scalar >= curve.prime_order {
+            return Err(Error::InvalidScalar);
+        }
+        Ok(Self { curve, scalar })
+    }
+
+    /// Computes the public key d * G to send to the peer.
+    pub fn public_key(&self, pka: &mut Pka) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        let generator = AffinePoint::generator(self.curve);
+        pka.ecc_mul(self.curve, &self.scalar, &generator, EccMulMode::Protected)
+    }
+
+    /// Computes the shared secret with the peer public key.
+    ///
+    /// [`Pka::ecc_mul`] runs [`Pka::validate_public_key`] on the peer key
+    /// before it is used: the point at infinity, coordinates not smaller than
+    /// p and points off the curve are rejected.
+    pub fn diffie_hellman(
+        &self,
+        pka: &mut Pka,
+        peer: &AffinePoint<MODULUS_SIZE>,
+    ) -> Result<SharedSecret<MODULUS_SIZE>, Error> {
+        let mut point = pka.ecc_mul(self.curve, &self.scalar, peer, EccMulMode::Protected)?;
+        let secret = SharedSecret { x: point.x };
+        point.x.zeroize();
+        point.y.zeroize();
+        Ok(secret)
+    }
+
+    /// Async counterpart of [`EphemeralSecret::public_key`].
+    pub async fn public_key_async(
+        &self,
+        pka: &mut Pka,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        let generator = AffinePoint::generator(self.curve);
+        pka.ecc_mul_async(self.curve, &self.scalar, &generator, EccMulMode::Protected)
+            .await
+    }
+
+    /// Async counterpart of [`EphemeralSecret::diffie_hellman`].
+    pub async fn diffie_hellman_async(
+        &self,
+        pka: &mut Pka,
+        peer: &AffinePoint<MODULUS_SIZE>,
+    ) -> Result<SharedSecret<MODULUS_SIZE>, Error> {
+        let mut point = pka
+            .ecc_mul_async(self.curve, &self.scalar, peer, EccMulMode::Protected)
+            .await?;
+        let secret = SharedSecret { x: point.x };
+        point.x.zeroize();
+        point.y.zeroize();
+        Ok(secret)
+    }
+}
+
+/// Wipes the scalar; scalar multiplications with it fail afterwards.
+impl<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> Zeroize
+    for EphemeralSecret<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+    fn zeroize(&mut self) {
+        self.scalar.zeroize();
+    }
+}
+
+impl<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> Drop
+    for EphemeralSecret<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> ZeroizeOnDrop
+    for EphemeralSecret<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+}
+
+/// ECDH shared secret: the x coordinate of the shared point.
+///
+/// This is raw key material and should go through a KDF before use. It is
+/// wiped when it is dropped.
+pub struct SharedSecret<const MODULUS_SIZE: usize> {
+    x: Uint<MODULUS_SIZE>,
+}
+
+impl<const MODULUS_SIZE: usize> SharedSecret<MODULUS_SIZE> {
+    /// Shared x coordinate.
+    pub fn raw_secret(&self) -> &Uint<MODULUS_SIZE> {
+        &self.x
+    }
+
+    /// Writes the shared x coordinate to `out` as big-endian bytes
+    /// (the field element encoding of SEC 1).
+    pub fn raw_secret_bytes(&self, out: &mut [u8]) -> Result<(), uint::Error> {
+        self.x.to_be_bytes(out)
+    }
+}
+
+impl<const MODULUS_SIZE: usize> Zeroize for SharedSecret<MODULUS_SIZE> {
+    fn zeroize(&mut self) {
+        self.x.zeroize();
+    }
+}
+
+impl<const MODULUS_SIZE: usize> Drop for SharedSecret<MODULUS_SIZE> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const MODULUS_SIZE: usize> ZeroizeOnDrop for SharedSecret<MODULUS_SIZE> {}
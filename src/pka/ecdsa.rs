@@ -0,0 +1,769 @@
+//! ECDSA signing and verification.
+
+use rand_core::{CryptoRng, RngCore};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+#[cfg(feature = "blinding")]
+use zeroize::Zeroizing;
+
+#[cfg(not(feature = "blinding"))]
+use super::{layout::ecdsa_sign, RESULT_OUT_OF_RANGE};
+use super::{
+    layout::ecdsa_verify, AffinePoint, EccMulMode, EllipticCurve, Error, Fault, Pka,
+    PkaOpcode, RESULT_ERROR,
+};
+use crate::uint::Uint;
+
+/// Result codes of ECDSA signing.
+#[cfg(not(feature = "blinding"))]
+const fn sign_result(raw: u32) -> Result<(), Error> {
+    match raw {
+        RESULT_ERROR => Err(Error::Rzero),
+        RESULT_OUT_OF_RANGE => Err(Error::Szero),
+        raw => Error::from_raw(raw),
+    }
+}
+
+/// Result codes of ECDSA verification.
+const fn verify_result(raw: u32) -> Result<(), Error> {
+    match raw {
+        RESULT_ERROR => Err(Error::InvalidSignature),
+        raw => Error::from_raw(raw),
+    }
+}
+
+impl Pka {
+    /// Signs `hash` with the nonce k and the private key d.
+    ///
+    /// `pub_key` is the public key dG. With [`Pka::set_verify_after_sign`]
+    /// the signature is verified against it before `r_sign` and `s_sign` are
+    /// written; otherwise it is not used.
+    ///
+    /// Without the `blinding` feature k and d are loaded into the PKA ECDSA
+    /// operation as they are. With it, kG is computed in
+    /// [`EccMulMode::Protected`] and the signature is put together from
+    /// blinded modular operations, so the PKA RAM only ever holds shares of
+    /// k and d.
+    #[allow(clippy::too_many_arguments)]
+    pub fn ecdsa_sign<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        nonce: &Uint<PRIME_ORDER_SIZE>,
+        priv_key: &Uint<PRIME_ORDER_SIZE>,
+        pub_key: &EcdsaPublicKey<'_, MODULUS_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+        r_sign: &mut Uint<MODULUS_SIZE>,
+        s_sign: &mut Uint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        let (mut r, mut s) = self.ecdsa_sign_unchecked(curve, nonce, priv_key, hash)?;
+        if self.verify_after_sign {
+            self.check_ecdsa_signature(curve, pub_key, hash, &mut r, &mut s)?;
+        }
+        *r_sign = r;
+        *s_sign = s;
+        Ok(())
+    }
+
+    /// Runs the PKA ECDSA signing, without [`Pka::verify_after_sign`].
+    #[cfg(not(feature = "blinding"))]
+    fn ecdsa_sign_unchecked<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        nonce: &Uint<PRIME_ORDER_SIZE>,
+        priv_key: &Uint<PRIME_ORDER_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        let mut r_sign = Uint::ZERO;
+        let mut s_sign = Uint::ZERO;
+        self.clear_all_flags();
+        self.ecdsa_sign_start(curve, nonce, priv_key, hash)?;
+        self.wait_for_completion()?;
+        self.ecdsa_sign_result(curve, &mut r_sign, &mut s_sign)?;
+        Ok((r_sign, s_sign))
+    }
+
+    /// Computes kG in [`EccMulMode::Protected`], then the signature with
+    /// [`Pka::ecdsa_blinded_signature`], without [`Pka::verify_after_sign`].
+    #[cfg(feature = "blinding")]
+    fn ecdsa_sign_unchecked<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        nonce: &Uint<PRIME_ORDER_SIZE>,
+        priv_key: &Uint<PRIME_ORDER_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        let generator = AffinePoint::generator(curve);
+        let point = self.ecc_mul(curve, nonce, &generator, EccMulMode::Protected)?;
+        self.ecdsa_blinded_signature(curve, nonce, priv_key, hash, &point)
+    }
+
+    /// Verifies a signature just computed with the private key of `pub_key`,
+    /// the check run by [`Pka::set_verify_after_sign`].
+    ///
+    /// If it does not verify, `r_sign` and `s_sign` are wiped along with the
+    /// PKA RAM and [`Error::FaultDetected`] is returned. The public key is
+    /// trusted and not validated.
+    pub fn check_ecdsa_signature<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        pub_key: &EcdsaPublicKey<'_, MODULUS_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+        r_sign: &mut Uint<MODULUS_SIZE>,
+        s_sign: &mut Uint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        let sig = EcdsaSignature { r_sign, s_sign };
+        self.clear_all_flags();
+        let verified = self
+            .ecdsa_verify_start(curve, &sig, pub_key, hash)
+            .and_then(|()| self.wait_for_completion())
+            .and_then(|()| self.ecdsa_verify_result());
+        self.signature_checked(verified, r_sign, s_sign)
+    }
+
+    /// Discards the signature if its check failed.
+    fn signature_checked<const MODULUS_SIZE: usize>(
+        &mut self,
+        verified: Result<(), Error>,
+        r_sign: &mut Uint<MODULUS_SIZE>,
+        s_sign: &mut Uint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        if verified.is_ok() {
+            return Ok(());
+        }
+        r_sign.zeroize();
+        s_sign.zeroize();
+        match verified {
+            Err(Error::Timeout) => Err(Error::Timeout),
+            _ => self.fail(Error::FaultDetected),
+        }
+    }
+
+    /// Computes r = x(kG) mod n and s = k<sup>-1</sup> (z + r d) mod n from
+    /// `point` = kG without handing k or d to the PKA as they are:
+    ///
+    /// s = (k<sub>1</sub> b + k<sub>2</sub> b)<sup>-1</sup>
+    /// (b z + b r d<sub>1</sub> + b r d<sub>2</sub>)
+    ///
+    /// for a random b and random splits k = k<sub>1</sub> + k<sub>2</sub> and
+    /// d = d<sub>1</sub> + d<sub>2</sub>, all drawn again for every
+    /// signature. The splits are computed in software without branching on
+    /// k or d, see [`split`], so the PKA RAM only ever holds shares.
+    #[cfg(feature = "blinding")]
+    fn ecdsa_blinded_signature<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        nonce: &Uint<PRIME_ORDER_SIZE>,
+        priv_key: &Uint<PRIME_ORDER_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+        point: &AffinePoint<MODULUS_SIZE>,
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        let n = &curve.prime_order;
+        self.fault_point(Fault::EcdsaR);
+        let r = self.mod_red(&point.x, n)?;
+        if r.is_zero() {
+            return Err(Error::Rzero);
+        }
+        let z = self.mod_red(hash, n)?;
+
+        let mut rng = self.rng();
+        let blind = Zeroizing::new(Uint::random_nonzero(&mut rng, n));
+        let d1 = Zeroizing::new(Uint::random_nonzero(&mut rng, n));
+        let d2 = Zeroizing::new(split(priv_key, &d1, n));
+        let k1 = Zeroizing::new(Uint::random_nonzero(&mut rng, n));
+        let k2 = Zeroizing::new(split(nonce, &k1, n));
+
+        let k1b = Zeroizing::new(self.mod_mul(&k1, &blind, n)?);
+        let k2b = Zeroizing::new(self.mod_mul(&k2, &blind, n)?);
+        let kb = Zeroizing::new(self.mod_add(&k1b, &k2b, n)?);
+        let kb_inv = Zeroizing::new(self.mod_inv(&kb, n)?);
+        let br = Zeroizing::new(self.mod_mul(&blind, &r, n)?);
+        let mut sum = Zeroizing::new(self.mod_mul(&blind, &z, n)?);
+        for d in [&d1, &d2] {
+            let term = Zeroizing::new(self.mod_mul(&br, d, n)?);
+            *sum = self.mod_add(&sum, &term, n)?;
+        }
+        self.fault_point(Fault::EcdsaS);
+        let s = self.mod_mul(&kb_inv, &sum, n)?;
+        if s.is_zero() {
+            return Err(Error::Szero);
+        }
+
+        let r_sign = r.resize().map_err(|_| Error::OutOfRange)?;
+        let s_sign = s.resize().map_err(|_| Error::OutOfRange)?;
+        Ok((r_sign, s_sign))
+    }
+
+    /// Loads the operands and starts the signature.
+    #[cfg(not(feature = "blinding"))]
+    fn ecdsa_sign_start<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        nonce: &Uint<PRIME_ORDER_SIZE>,
+        priv_key: &Uint<PRIME_ORDER_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(), Error> {
+        self.zero_ram();
+
+        self.write_word(ecdsa_sign::N_LEN, curve.prime_order_length());
+        self.write_word(ecdsa_sign::P_LEN, curve.modulus_length());
+        self.write_word(ecdsa_sign::A_SIGN, curve.coef_sign.into());
+        self.write_uint(ecdsa_sign::A, &curve.coef_a)?;
+        self.write_uint(ecdsa_sign::B, &curve.coef_b)?;
+        self.write_uint(ecdsa_sign::P, &curve.modulus)?;
+        self.write_uint(ecdsa_sign::K, nonce)?;
+        self.write_uint(ecdsa_sign::X, &curve.base_point_x)?;
+        self.write_uint(ecdsa_sign::Y, &curve.base_point_y)?;
+        self.write_uint(ecdsa_sign::Z, hash)?;
+        self.write_uint(ecdsa_sign::D, priv_key)?;
+        self.write_uint(ecdsa_sign::N, &curve.prime_order)?;
+
+        self.start(PkaOpcode::EcdsaSign)
+    }
+
+    /// Reads the signature computed by [`Pka::ecdsa_sign_start`].
+    ///
+    /// Returns [`Error::Rzero`] or [`Error::Szero`] when the nonce must be
+    /// replaced.
+    #[cfg(not(feature = "blinding"))]
+    fn ecdsa_sign_result<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        r_sign: &mut Uint<MODULUS_SIZE>,
+        s_sign: &mut Uint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        let mode = self.mode();
+        if mode != PkaOpcode::EcdsaSign as u8 {
+            return Error::mode(mode);
+        }
+        if !self.is_done() {
+            return Err(Error::Busy);
+        }
+        self.check_flags()?;
+        self.result_code(ecdsa_sign::OUT_RESULT, sign_result)?;
+
+        self.fault_point(Fault::EcdsaR);
+        *r_sign = self.read_uint(ecdsa_sign::OUT_R, curve.prime_order_length());
+        self.fault_point(Fault::EcdsaS);
+        *s_sign = self.read_uint(ecdsa_sign::OUT_S, curve.prime_order_length());
+        // The RAM holds the private key and the nonce
+        self.zero_ram();
+        Ok(())
+    }
+
+    /// Verifies `sig` over `hash`.
+    ///
+    /// r and s must both lie in [1, n - 1], or [`Error::InvalidSignature`] is
+    /// returned without starting the PKA. `pub_key` is untrusted input and
+    /// goes through [`Pka::validate_public_key`] before the verification is
+    /// started.
+    pub fn ecdsa_verify<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        sig: &EcdsaSignature<MODULUS_SIZE>,
+        pub_key: &EcdsaPublicKey<MODULUS_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(), Error> {
+        check_signature_range(curve, sig)?;
+        self.validate_public_key(curve, pub_key)?;
+        self.clear_all_flags();
+        self.ecdsa_verify_start(curve, sig, pub_key, hash)?;
+        self.wait_for_completion()?;
+        self.ecdsa_verify_result()
+    }
+
+    /// Loads the operands and starts the verification.
+    ///
+    /// Unlike [`Pka::ecdsa_verify`] this does not validate `pub_key`.
+    pub fn ecdsa_verify_start<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        sig: &EcdsaSignature<MODULUS_SIZE>,
+        pub_key: &EcdsaPublicKey<MODULUS_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(), Error> {
+        self.zero_ram();
+
+        self.write_word(ecdsa_verify::N_LEN, curve.prime_order_length());
+        self.write_word(ecdsa_verify::P_LEN, curve.modulus_length());
+        self.write_word(ecdsa_verify::A_SIGN, curve.coef_sign.into());
+        self.write_uint(ecdsa_verify::A, &curve.coef_a)?;
+        self.write_uint(ecdsa_verify::P, &curve.modulus)?;
+        self.write_uint(ecdsa_verify::X, &curve.base_point_x)?;
+        self.write_uint(ecdsa_verify::Y, &curve.base_point_y)?;
+        self.write_uint(ecdsa_verify::XQ, pub_key.curve_pt_x)?;
+        self.write_uint(ecdsa_verify::YQ, pub_key.curve_pt_y)?;
+        self.write_uint(ecdsa_verify::R, sig.r_sign)?;
+        self.write_uint(ecdsa_verify::S, sig.s_sign)?;
+        self.write_uint(ecdsa_verify::Z, hash)?;
+        self.write_uint(ecdsa_verify::N, &curve.prime_order)?;
+        self.start(PkaOpcode::EcdsaVerify)
+    }
+
+    /// Reads the outcome of the verification started by
+    /// [`Pka::ecdsa_verify_start`].
+    ///
+    /// Returns [`Error::InvalidSignature`] if the signature does not match.
+    pub fn ecdsa_verify_result(&mut self) -> Result<(), Error> {
+        let mode = self.mode();
+        if mode != PkaOpcode::EcdsaVerify as u8 {
+            return Error::mode(mode);
+        }
+        if !self.is_done() {
+            return Err(Error::Busy);
+        }
+        self.check_flags()?;
+        self.result_code(ecdsa_verify::OUT, verify_result)
+    }
+}
+
+// Async variants: the operation is started the same way, then the PKA
+// interrupt is awaited before reading the result.
+impl Pka {
+    /// Async counterpart of [`Pka::ecdsa_sign`].
+    #[allow(clippy::too_many_arguments)]
+    pub async fn ecdsa_sign_async<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        nonce: &Uint<PRIME_ORDER_SIZE>,
+        priv_key: &Uint<PRIME_ORDER_SIZE>,
+        pub_key: &EcdsaPublicKey<'_, MODULUS_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+        r_sign: &mut Uint<MODULUS_SIZE>,
+        s_sign: &mut Uint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        let (mut r, mut s) = self.ecdsa_sign_unchecked_async(curve, nonce, priv_key, hash).await?;
+        if self.verify_after_sign {
+            self.check_ecdsa_signature_async(curve, pub_key, hash, &mut r, &mut s).await?;
+        }
+        *r_sign = r;
+        *s_sign = s;
+        Ok(())
+    }
+
+    /// Async counterpart of [`Pka::ecdsa_sign_unchecked`].
+    #[cfg(not(feature = "blinding"))]
+    async fn ecdsa_sign_unchecked_async<
+        const MODULUS_SIZE: usize,
+        const PRIME_ORDER_SIZE: usize,
+    >(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        nonce: &Uint<PRIME_ORDER_SIZE>,
+        priv_key: &Uint<PRIME_ORDER_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        let mut r_sign = Uint::ZERO;
+        let mut s_sign = Uint::ZERO;
+        self.clear_all_flags();
+        self.ecdsa_sign_start(curve, nonce, priv_key, hash)?;
+        self.wait_for_completion_async().await?;
+        self.ecdsa_sign_result(curve, &mut r_sign, &mut s_sign)?;
+        Ok((r_sign, s_sign))
+    }
+
+    /// Async counterpart of [`Pka::ecdsa_sign_unchecked`].
+    #[cfg(feature = "blinding")]
+    async fn ecdsa_sign_unchecked_async<
+        const MODULUS_SIZE: usize,
+        const PRIME_ORDER_SIZE: usize,
+    >(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        nonce: &Uint<PRIME_ORDER_SIZE>,
+        priv_key: &Uint<PRIME_ORDER_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        let generator = AffinePoint::generator(curve);
+        let point = self
+            .ecc_mul_async(curve, nonce, &generator, EccMulMode::Protected)
+            .await?;
+        self.ecdsa_blinded_signature(curve, nonce, priv_key, hash, &point)
+    }
+
+    /// Async counterpart of [`Pka::check_ecdsa_signature`].
+    pub async fn check_ecdsa_signature_async<
+        const MODULUS_SIZE: usize,
+        const PRIME_ORDER_SIZE: usize,
+    >(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        pub_key: &EcdsaPublicKey<'_, MODULUS_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+        r_sign: &mut Uint<MODULUS_SIZE>,
+        s_sign: &mut Uint<MODULUS_SIZE>,
+    ) -> Result<(), Error> {
+        let sig = EcdsaSignature { r_sign, s_sign };
+        self.clear_all_flags();
+        let mut verified = self.ecdsa_verify_start(curve, &sig, pub_key, hash);
+        if verified.is_ok() {
+            verified = self.wait_for_completion_async().await;
+        }
+        let verified = verified.and_then(|()| self.ecdsa_verify_result());
+        self.signature_checked(verified, r_sign, s_sign)
+    }
+
+    /// Async counterpart of [`Pka::ecdsa_verify`].
+    pub async fn ecdsa_verify_async<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &mut self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        sig: &EcdsaSignature<'_, MODULUS_SIZE>,
+        pub_key: &EcdsaPublicKey<'_, MODULUS_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(), Error> {
+        check_signature_range(curve, sig)?;
+        self.validate_public_key_async(curve, pub_key).await?;
+        self.clear_all_flags();
+        self.ecdsa_verify_start(curve, sig, pub_key, hash)?;
+        self.wait_for_completion_async().await?;
+        self.ecdsa_verify_result()
+    }
+}
+
+/// Checks 1 <= r, s < n before the signature is loaded into the PKA.
+fn check_signature_range<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+    curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    sig: &EcdsaSignature<MODULUS_SIZE>,
+) -> Result<(), Error> {
+    let in_range = |value: &Uint<MODULUS_SIZE>| {
+        !value.is_zero()
+            && value
+                .resize::<PRIME_ORDER_SIZE>()
+                .is_ok_and(|value| value < curve.prime_order)
+    };
+    if in_range(sig.r_sign) && in_range(sig.s_sign) {
+        Ok(())
+    } else {
+        Err(Error::InvalidSignature)
+    }
+}
+
+/// ECDSA signature.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EcdsaSignature<'a, const MODULUS_SIZE: usize> {
+    /// Signature part r.
+    pub r_sign: &'a Uint<MODULUS_SIZE>,
+    /// Signature part s.
+    pub s_sign: &'a Uint<MODULUS_SIZE>,
+}
+
+#[cfg(feature = "defmt")]
+impl<'a, const MODULUS_SIZE: usize> defmt::Format for EcdsaSignature<'a, MODULUS_SIZE> {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "EcdsaSignature {{ r_sign: {}, s_sign: {} }}",
+            self.r_sign,
+            self.s_sign
+        )
+    }
+}
+
+/// ECDSA public key.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EcdsaPublicKey<'a, const MODULUS_SIZE: usize> {
+    /// Public-key curve point xQ.
+    pub curve_pt_x: &'a Uint<MODULUS_SIZE>,
+    /// Public-key curve point yQ.
+    pub curve_pt_y: &'a Uint<MODULUS_SIZE>,
+}
+
+/// ECDSA private key d with its public key Q = dG.
+///
+/// The private scalar is wiped when the key is dropped.
+pub struct SigningKey<'c, const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> {
+    curve: &'c EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    secret: Uint<PRIME_ORDER_SIZE>,
+    public: AffinePoint<MODULUS_SIZE>,
+}
+
+impl<'c, const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>
+    SigningKey<'c, MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+    /// Generates a new key pair: d is drawn in [1, n - 1] and Q = dG is
+    /// computed on the PKA in [`EccMulMode::Protected`].
+    pub fn generate<R: RngCore + CryptoRng>(
+        pka: &mut Pka,
+        rng: &mut R,
+        curve: &'c EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> Result<Self, Error> {
+        let secret = Uint::random_nonzero(rng, &curve.prime_order);
+        Self::from_scalar(pka, curve, secret)
+    }
+
+    /// Loads an existing private key and derives its public key.
+    ///
+    /// Returns [`Error::InvalidScalar`] unless `secret` is in [1, n - 1].
+    pub fn from_scalar(
+        pka: &mut Pka,
+        curve: &'c EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        mut secret: Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<Self, Error> {
+        let generator = AffinePoint::generator(curve);
+        match pka.ecc_mul(curve, &secret, &generator, EccMulMode::Protected) {
+            Ok(public) => Ok(Self {
+                curve,
+                secret,
+                public,
+            }),
+            Err(e) => {
+                secret.zeroize();
+                Err(e)
+            }
+        }
+    }
+
+    /// Public key matching this private key.
+    pub fn verifying_key(&self) -> VerifyingKey<'c, MODULUS_SIZE, PRIME_ORDER_SIZE> {
+        VerifyingKey {
+            curve: self.curve,
+            point: self.public,
+        }
+    }
+
+    /// Signs `hash` with a fresh random nonce.
+    ///
+    /// A new nonce is drawn if the PKA reports r = 0 or s = 0. Returns
+    /// [`Error::InvalidScalar`] once the key has been zeroized.
+    ///
+    /// With the `blinding` feature the signature is blinded as in
+    /// [`Pka::ecdsa_sign`].
+    ///
+    /// With [`Pka::set_verify_after_sign`] the signature is verified against
+    /// the public key before it is returned.
+    pub fn sign<R: RngCore + CryptoRng>(
+        &self,
+        pka: &mut Pka,
+        rng: &mut R,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        // Wiped by `zeroize`
+        if self.secret.is_zero() {
+            return Err(Error::InvalidScalar);
+        }
+        loop {
+            let mut nonce = Uint::random_nonzero(rng, &self.curve.prime_order);
+            let result = self.sign_with_nonce(pka, &nonce, hash);
+            nonce.zeroize();
+            match result {
+                Err(Error::Rzero | Error::Szero) => continue,
+                Ok((mut r_sign, mut s_sign)) if pka.verify_after_sign() => {
+                    pka.check_ecdsa_signature(
+                        self.curve,
+                        &EcdsaPublicKey {
+                            curve_pt_x: &self.public.x,
+                            curve_pt_y: &self.public.y,
+                        },
+                        hash,
+                        &mut r_sign,
+                        &mut s_sign,
+                    )?;
+                    return Ok((r_sign, s_sign));
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Async counterpart of [`SigningKey::sign`].
+    pub async fn sign_async<R: RngCore + CryptoRng>(
+        &self,
+        pka: &mut Pka,
+        rng: &mut R,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        // Wiped by `zeroize`
+        if self.secret.is_zero() {
+            return Err(Error::InvalidScalar);
+        }
+        loop {
+            let mut nonce = Uint::random_nonzero(rng, &self.curve.prime_order);
+            let result = self.sign_with_nonce_async(pka, &nonce, hash).await;
+            nonce.zeroize();
+            match result {
+                Err(Error::Rzero | Error::Szero) => continue,
+                Ok((mut r_sign, mut s_sign)) if pka.verify_after_sign() => {
+                    pka.check_ecdsa_signature_async(
+                        self.curve,
+                        &EcdsaPublicKey {
+                            curve_pt_x: &self.public.x,
+                            curve_pt_y: &self.public.y,
+                        },
+                        hash,
+                        &mut r_sign,
+                        &mut s_sign,
+                    )
+                    .await?;
+                    return Ok((r_sign, s_sign));
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn sign_with_nonce(
+        &self,
+        pka: &mut Pka,
+        nonce: &Uint<PRIME_ORDER_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        pka.ecdsa_sign_unchecked(self.curve, nonce, &self.secret, hash)
+    }
+
+    async fn sign_with_nonce_async(
+        &self,
+        pka: &mut Pka,
+        nonce: &Uint<PRIME_ORDER_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        pka.ecdsa_sign_unchecked_async(self.curve, nonce, &self.secret, hash).await
+    }
+}
+/// Returns `secret - share mod n`, for `secret, share < n`.
+///
+/// Unlike [`Uint::sub_mod`], the reduction does not branch on the borrow,
+/// which would leak whether `share` is larger than `secret`.
+#[cfg(feature = "blinding")]
+fn split<const LIMBS: usize>(
+    secret: &Uint<LIMBS>,
+    share: &Uint<LIMBS>,
+    n: &Uint<LIMBS>,
+) -> Uint<LIMBS> {
+    let (mut diff, borrow) = secret.sbb(share);
+    let other = diff.conditional_add(n, borrow == 1);
+    diff.zeroize();
+    other
+}
+
+impl<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> Zeroize
+    for SigningKey<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+    fn zeroize(&mut self) {
+        self.secret.zeroize();
+    }
+}
+
+impl<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> Drop
+    for SigningKey<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> ZeroizeOnDrop
+    for SigningKey<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+}
+
+/// ECDSA public key Q bound to its curve.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct VerifyingKey<'c, const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> {
+    curve: &'c EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    point: AffinePoint<MODULUS_SIZE>,
+}
+
+impl<'c, const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>
+    VerifyingKey<'c, MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+    /// Wraps a public key received from elsewhere.
+    pub const fn new(
+        curve: &'c EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        point: AffinePoint<MODULUS_SIZE>,
+    ) -> Self {
+        Self { curve, point }
+    }
+
+    /// Curve the key belongs to.
+    pub const fn curve(&self) -> &'c EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE> {
+        self.curve
+    }
+
+    /// Public key point Q.
+    pub const fn as_affine(&self) -> &AffinePoint<MODULUS_SIZE> {
+        &self.point
+    }
+
+    /// Decodes a SEC1 public key, compressed or uncompressed; see
+    /// [`AffinePoint::from_sec1_bytes`].
+    pub fn from_sec1_bytes(
+        pka: &mut Pka,
+        curve: &'c EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        bytes: &[u8],
+    ) -> Result<Self, Error> {
+        let point = AffinePoint::from_sec1_bytes(pka, curve, bytes)?;
+        Ok(Self::new(curve, point))
+    }
+
+    /// Encodes the public key in SEC1 form; see
+    /// [`AffinePoint::to_sec1_bytes`].
+    pub fn to_sec1_bytes(&self, compress: bool, out: &mut [u8]) -> Result<usize, Error> {
+        self.point.to_sec1_bytes(self.curve, compress, out)
+    }
+
+    /// Public key in the form taken by [`Pka::ecdsa_verify`].
+    pub const fn public_key(&self) -> EcdsaPublicKey<'_, MODULUS_SIZE> {
+        EcdsaPublicKey {
+            curve_pt_x: &self.point.x,
+            curve_pt_y: &self.point.y,
+        }
+    }
+
+    /// Verifies the signature (`r_sign`, `s_sign`) of `hash`.
+    pub fn verify(
+        &self,
+        pka: &mut Pka,
+        r_sign: &Uint<MODULUS_SIZE>,
+        s_sign: &Uint<MODULUS_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(), Error> {
+        let sig = EcdsaSignature { r_sign, s_sign };
+        pka.ecdsa_verify(self.curve, &sig, &self.public_key(), hash)
+    }
+
+    /// Async counterpart of [`VerifyingKey::verify`].
+    pub async fn verify_async(
+        &self,
+        pka: &mut Pka,
+        r_sign: &Uint<MODULUS_SIZE>,
+        s_sign: &Uint<MODULUS_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(), Error> {
+        let sig = EcdsaSignature { r_sign, s_sign };
+        pka.ecdsa_verify_async(self.curve, &sig, &self.public_key(), hash)
+            .await
+    }
+
+    /// Verifies a DER-encoded signature of `hash`.
+    ///
+    /// The signature is parsed with [`EcdsaSignature::from_der`], so any
+    /// encoding other than the strict DER one fails with
+    /// [`Error::Encoding`].
+    pub fn verify_der(
+        &self,
+        pka: &mut Pka,
+        der: &[u8],
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(), Error> {
+        let (r_sign, s_sign) = EcdsaSignature::from_der(self.curve, der)?;
+        self.verify(pka, &r_sign, &s_sign, hash)
+    }
+
+    /// Async counterpart of [`VerifyingKey::verify_der`].
+    pub async fn verify_der_async(
+        &self,
+        pka: &mut Pka,
+        der: &[u8],
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(), Error> {
+        let (r_sign, s_sign) = EcdsaSignature::from_der(self.curve, der)?;
+        self.verify_async(pka, &r_sign, &s_sign, hash).await
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize> defmt::Format
+    for VerifyingKey<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>
+{
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "VerifyingKey {{ x: {}, y: {} }}", self.point.x, self.point.y)
+    }
+}
@@ -0,0 +1,402 @@
+//! Ed25519 signatures (RFC 8032).
+//!
+//! The PKA has no Edwards curve support, so the group operations run on
+//! [`WEI25519`], the short Weierstrass form of the same curve. Points are
+//! mapped between the Edwards encoding and Wei25519 with PKA field
+//! operations; SHA-512 runs in software.
+//!
+//! Verification checks [S]B = R + [k]A, the equation RFC 8032, section
+//! 5.1.7 allows in place of the cofactored one, by comparing the encoding of
+//! the right-hand side with R. A non-canonical R never matches.
+
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use super::{
+    curve::{WEI25519, WEI25519_U_OFFSET},
+    AffinePoint, EccMulMode, Error as PkaError, Pka,
+};
+use crate::uint::{self, Uint};
+
+/// Length of a secret key (the seed), in bytes.
+pub const SECRET_KEY_LENGTH: usize = 32;
+/// Length of an encoded public key, in bytes.
+pub const PUBLIC_KEY_LENGTH: usize = 32;
+/// Length of a signature, in bytes.
+pub const SIGNATURE_LENGTH: usize = 64;
+
+/// Field modulus p = 2<sup>255</sup> - 19.
+const P: Uint<8> = WEI25519.modulus;
+/// Order L of the base point.
+const L: Uint<8> = WEI25519.prime_order;
+/// Edwards curve constant d = -121665 / 121666.
+const EDWARDS_D: Uint<8> =
+    Uint::from_be_hex("52036cee2b6ffe738cc740797779e89800700a4d4141d8ab75eb4dca135978a3");
+/// Square root of -1.
+const SQRT_M1: Uint<8> =
+    Uint::from_be_hex("2b8324804fc1df0b2b4d00993dfbd7a72f431806ad2fe478c4ee1b274a0ea0b0");
+/// (p + 3) / 8, the exponent of the square root candidate.
+const SQRT_EXPONENT: Uint<8> =
+    Uint::from_be_hex("0ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe");
+/// Square root of -(A + 2), scaling Montgomery v = c * u / x.
+const MONTGOMERY_C: Uint<8> =
+    Uint::from_be_hex("0f26edf460a006bbd27b08dc03fc4f7ec5a1d3d14b7d1a82cc6e04aaff457e06");
+
+/// Errors from Ed25519 key handling, signing and verification.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// A PKA operation failed.
+    Pka(PkaError),
+    /// A public key or signature is not a valid encoding.
+    Encoding,
+    /// The signature does not match the message and public key.
+    Invalid,
+}
+
+impl From<PkaError> for Error {
+    fn from(e: PkaError) -> Self {
+        Error::Pka(e)
+    }
+}
+
+impl From<uint::Error> for Error {
+    fn from(_: uint::Error) -> Self {
+        Error::Encoding
+    }
+}
+
+/// Ed25519 signature: the encoded point R followed by the scalar S.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Signature {
+    r: [u8; 32],
+    s: [u8; 32],
+}
+
+impl Signature {
+    /// Splits a 64-byte signature into R and S.
+    ///
+    /// S is only range checked by [`VerifyingKey::verify`].
+    pub fn from_bytes(bytes: &[u8; SIGNATURE_LENGTH]) -> Self {
+        let mut r = [0; 32];
+        let mut s = [0; 32];
+        r.copy_from_slice(&bytes[..32]);
+        s.copy_from_slice(&bytes[32..]);
+        Self { r, s }
+    }
+
+    /// Encodes the signature as R || S.
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_LENGTH] {
+        let mut bytes = [0; SIGNATURE_LENGTH];
+        bytes[..32].copy_from_slice(&self.r);
+        bytes[32..].copy_from_slice(&self.s);
+        bytes
+    }
+
+    /// Encoded point R.
+    pub const fn r_bytes(&self) -> &[u8; 32] {
+        &self.r
+    }
+
+    /// Scalar S, little-endian.
+    pub const fn s_bytes(&self) -> &[u8; 32] {
+        &self.s
+    }
+}
+
+/// Ed25519 signing key.
+///
+/// Holds the seed and the values derived from it; all of them are wiped when
+/// the key is dropped. Multiplications with the secret scalar and the nonce
+/// run in [`EccMulMode::Protected`].
+pub struct SigningKey {
+    seed: [u8; SECRET_KEY_LENGTH],
+    scalar: Uint<8>,
+    prefix: [u8; 32],
+    verifying_key: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Generates a key from a fresh random seed.
+    pub fn generate<R: RngCore + CryptoRng>(pka: &mut Pka, rng: &mut R) -> Result<Self, Error> {
+        let mut seed = [0; SECRET_KEY_LENGTH];
+        rng.fill_bytes(&mut seed);
+        let key = Self::from_bytes(pka, &seed);
+        seed.zeroize();
+        key
+    }
+
+    /// Expands a 32-byte seed (the RFC 8032 secret key) and computes the
+    /// public key.
+    pub fn from_bytes(pka: &mut Pka, seed: &[u8; SECRET_KEY_LENGTH]) -> Result<Self, Error> {
+        let mut digest = [0; 64];
+        digest.copy_from_slice(&Sha512::digest(seed));
+        digest[0] &= 248;
+        digest[31] &= 127;
+        digest[31] |= 64;
+
+        // B has order L, so s * B = (s mod L) * B and the PKA gets a scalar
+        // in [1, L - 1].
+        let mut clamped = Uint::<8>::from_le_bytes(&digest[..32])?;
+        let scalar = pka.mod_red(&clamped, &L);
+        clamped.zeroize();
+
+        let mut key = Self {
+            seed: *seed,
+            scalar: Uint::ZERO,
+            prefix: [0; 32],
+            verifying_key: VerifyingKey {
+                compressed: [0; PUBLIC_KEY_LENGTH],
+                point: AffinePoint::IDENTITY,
+            },
+        };
+        key.prefix.copy_from_slice(&digest[32..]);
+        digest.zeroize();
+        key.scalar = scalar?;
+
+        let generator = AffinePoint::generator(&WEI25519);
+        let point = pka.ecc_mul(&WEI25519, &key.scalar, &generator, EccMulMode::Protected)?;
+        key.verifying_key = VerifyingKey {
+            compressed: compress(pka, &point)?,
+            point,
+        };
+        Ok(key)
+    }
+
+    /// The seed this key was expanded from.
+    pub const fn as_bytes(&self) -> &[u8; SECRET_KEY_LENGTH] {
+        &self.seed
+    }
+
+    /// Returns the matching public key.
+    pub const fn verifying_key(&self) -> VerifyingKey {
+        self.verifying_key
+    }
+
+    /// Signs `message` (PureEd25519: the message is hashed as is).
+    pub fn sign(&self, pka: &mut Pka, message: &[u8]) -> Result<Signature, Error> {
+        // Wiped by `zeroize`
+        if self.scalar.is_zero() {
+            return Err(PkaError::InvalidScalar.into());
+        }
+        let mut nonce = hash_mod_l(pka, &[&self.prefix, message])?;
+        let signature = self.sign_with_nonce(pka, &nonce, message);
+        nonce.zeroize();
+        signature
+    }
+
+    fn sign_with_nonce(
+        &self,
+        pka: &mut Pka,
+        nonce: &Uint<8>,
+        message: &[u8],
+    ) -> Result<Signature, Error> {
+        let generator = AffinePoint::generator(&WEI25519);
+        let r_point = pka.ecc_mul(&WEI25519, nonce, &generator, EccMulMode::Protected)?;
+        let r = compress(pka, &r_point)?;
+        let k = hash_mod_l(pka, &[&r, &self.verifying_key.compressed, message])?;
+
+        // S = (r + k * s) mod L
+        let mut ks = pka.mod_mul(&k, &self.scalar, &L)?;
+        let s = pka.mod_add(nonce, &ks, &L);
+        ks.zeroize();
+        let mut s_bytes = [0; 32];
+        s?.to_le_bytes(&mut s_bytes)?;
+        Ok(Signature { r, s: s_bytes })
+    }
+}
+
+/// Wipes the seed and the expanded key; signing fails afterwards.
+impl Zeroize for SigningKey {
+    fn zeroize(&mut self) {
+        self.seed.zeroize();
+        self.scalar.zeroize();
+        self.prefix.zeroize();
+    }
+}
+
+impl Drop for SigningKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SigningKey {}
+
+/// Ed25519 public key.
+///
+/// Keeps both the 32-byte encoding, which is hashed when verifying, and the
+/// matching Wei25519 point.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct VerifyingKey {
+    compressed: [u8; PUBLIC_KEY_LENGTH],
+    point: AffinePoint<8>,
+}
+
+impl VerifyingKey {
+    /// Decodes a public key.
+    ///
+    /// Returns [`Error::Encoding`] if y is not smaller than p or no point
+    /// with this y exists, and [`Error::Invalid`] for the neutral element.
+    pub fn from_bytes(pka: &mut Pka, bytes: &[u8; PUBLIC_KEY_LENGTH]) -> Result<Self, Error> {
+        let point = decompress(pka, bytes)?;
+        if point.is_identity() {
+            return Err(Error::Invalid);
+        }
+        Ok(Self {
+            compressed: *bytes,
+            point,
+        })
+    }
+
+    /// The 32-byte encoding.
+    pub const fn as_bytes(&self) -> &[u8; PUBLIC_KEY_LENGTH] {
+        &self.compressed
+    }
+
+    /// The point on [`WEI25519`].
+    pub const fn as_affine(&self) -> &AffinePoint<8> {
+        &self.point
+    }
+
+    /// Verifies `signature` over `message`.
+    ///
+    /// Returns [`Error::Invalid`] if S is zero or not smaller than L, or the
+    /// signature does not match.
+    pub fn verify(
+        &self,
+        pka: &mut Pka,
+        message: &[u8],
+        signature: &Signature,
+    ) -> Result<(), Error> {
+        let s = Uint::<8>::from_le_bytes(&signature.s)?;
+        // The double base ladder takes scalars in [1, L - 1]
+        if s.is_zero() || s >= L {
+            return Err(Error::Invalid);
+        }
+        let k = hash_mod_l(pka, &[&signature.r, &self.compressed, message])?;
+
+        // [S]B - [k]A on the double base ladder
+        let generator = AffinePoint::generator(&WEI25519);
+        let minus_a = AffinePoint::new(self.point.x, self.point.y.neg_mod(&P));
+        let r = match pka.double_scalar_mul(&WEI25519, &s, &generator, &k, &minus_a) {
+            Ok(point) => point,
+            Err(PkaError::PointAtInfinity) => AffinePoint::IDENTITY,
+            // k = 0: H(R || A || M) is a multiple of L, negligibly rare
+            Err(PkaError::InvalidScalar) => return Err(Error::Invalid),
+            Err(e) => return Err(e.into()),
+        };
+
+        if compress(pka, &r)? == signature.r {
+            Ok(())
+        } else {
+            Err(Error::Invalid)
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for VerifyingKey {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "VerifyingKey {{ {=[u8]:x} }}", self.compressed)
+    }
+}
+
+/// SHA-512 of the concatenated `parts`, as a little-endian integer mod L.
+fn hash_mod_l(pka: &mut Pka, parts: &[&[u8]]) -> Result<Uint<8>, Error> {
+    let mut hasher = Sha512::new();
+    parts.iter().for_each(|part| hasher.update(part));
+    let mut wide = Uint::<16>::from_le_bytes(&hasher.finalize())?;
+    let reduced = pka.mod_red(&wide, &L);
+    wide.zeroize();
+    Ok(reduced?)
+}
+
+/// Encodes a Wei25519 point as an Edwards point: y little-endian with the
+/// low bit of x in the top bit.
+fn compress(pka: &mut Pka, point: &AffinePoint<8>) -> Result<[u8; 32], Error> {
+    let (x, y) = wei_to_edwards(pka, point)?;
+    let mut bytes = [0; 32];
+    y.to_le_bytes(&mut bytes)?;
+    bytes[31] |= (x.bit(0) as u8) << 7;
+    Ok(bytes)
+}
+
+/// Decodes an Edwards point (RFC 8032, section 5.1.3) onto Wei25519.
+fn decompress(pka: &mut Pka, bytes: &[u8; 32]) -> Result<AffinePoint<8>, Error> {
+    let sign = bytes[31] >> 7 == 1;
+    let mut y_bytes = *bytes;
+    y_bytes[31] &= 0x7f;
+    let y = Uint::<8>::from_le_bytes(&y_bytes)?;
+    if y >= P {
+        return Err(Error::Encoding);
+    }
+
+    // x^2 = (y^2 - 1) / (d y^2 + 1)
+    let y2 = pka.mod_mul(&y, &y, &P)?;
+    let u = pka.mod_sub(&y2, &Uint::ONE, &P)?;
+    let v = pka.mod_mul(&EDWARDS_D, &y2, &P)?;
+    let v = pka.mod_add(&v, &Uint::ONE, &P)?;
+    let v_inv = pka.mod_inv(&v, &P)?;
+    let w = pka.mod_mul(&u, &v_inv, &P)?;
+
+    let mut x = pka.mod_exp(&w, &SQRT_EXPONENT, &P)?;
+    let x2 = pka.mod_mul(&x, &x, &P)?;
+    if x2 != w {
+        if x2 != w.neg_mod(&P) {
+            return Err(Error::Encoding);
+        }
+        x = pka.mod_mul(&x, &SQRT_M1, &P)?;
+    }
+    if x.is_zero() && sign {
+        return Err(Error::Encoding);
+    }
+    if x.bit(0) != sign {
+        x = x.neg_mod(&P);
+    }
+    edwards_to_wei(pka, &x, &y)
+}
+
+/// Maps an Edwards point to Wei25519 through the Montgomery form:
+/// u = (1 + y) / (1 - y), v = c * u / x, then (u + A / 3, v).
+fn edwards_to_wei(pka: &mut Pka, x: &Uint<8>, y: &Uint<8>) -> Result<AffinePoint<8>, Error> {
+    // x = 0 only for the neutral element (0, 1) and the point (0, -1) of
+    // order 2, which maps to (0, 0) on the Montgomery curve.
+    if x.is_zero() {
+        return Ok(if *y == Uint::ONE {
+            AffinePoint::IDENTITY
+        } else {
+            AffinePoint::new(WEI25519_U_OFFSET, Uint::ZERO)
+        });
+    }
+    let num = pka.mod_add(&Uint::ONE, y, &P)?;
+    let den = pka.mod_sub(&Uint::ONE, y, &P)?;
+    let den_inv = pka.mod_inv(&den, &P)?;
+    let u = pka.mod_mul(&num, &den_inv, &P)?;
+    let cu = pka.mod_mul(&MONTGOMERY_C, &u, &P)?;
+    let x_inv = pka.mod_inv(x, &P)?;
+    let v = pka.mod_mul(&cu, &x_inv, &P)?;
+    Ok(AffinePoint::new(pka.mod_add(&u, &WEI25519_U_OFFSET, &P)?, v))
+}
+
+/// Inverse of [`edwards_to_wei`]: u = x - A / 3, y = (u - 1) / (u + 1),
+/// x = c * u / v.
+fn wei_to_edwards(pka: &mut Pka, point: &AffinePoint<8>) -> Result<(Uint<8>, Uint<8>), Error> {
+    if point.is_identity() {
+        return Ok((Uint::ZERO, Uint::ONE));
+    }
+    if point.y.is_zero() {
+        return Ok((Uint::ZERO, Uint::ONE.neg_mod(&P)));
+    }
+    let u = pka.mod_sub(&point.x, &WEI25519_U_OFFSET, &P)?;
+    let num = pka.mod_sub(&u, &Uint::ONE, &P)?;
+    let den = pka.mod_add(&u, &Uint::ONE, &P)?;
+    let den_inv = pka.mod_inv(&den, &P)?;
+    let y = pka.mod_mul(&num, &den_inv, &P)?;
+    let cu = pka.mod_mul(&MONTGOMERY_C, &u, &P)?;
+    let y_inv = pka.mod_inv(&point.y, &P)?;
+    let x = pka.mod_mul(&cu, &y_inv, &P)?;
+    Ok((x, y))
+}
@@ -0,0 +1,320 @@
+//! Byte encodings of ECDSA signatures and curve points.
+//!
+//! [`EcdsaSignature`] converts to and from two forms:
+//!
+//! - r || s, each big-endian and left-padded to the byte length of the curve
+//!   order (64 bytes on P-256), as used by the `p256` crate, JOSE and COSE;
+//! - ASN.1 DER `SEQUENCE { r INTEGER, s INTEGER }`, as produced and expected
+//!   by OpenSSL and X.509.
+//!
+//! DER is parsed strictly: lengths must use their shortest form, integers
+//! must be positive and minimal (a leading zero byte only in front of a set
+//! top bit), and no bytes may follow the sequence. Anything else is rejected
+//! with [`Error::Encoding`], so a signature has a single accepted encoding.
+//! r and s are not range checked here; [`Pka::ecdsa_verify`] rejects values
+//! outside [1, n - 1] with [`Error::InvalidSignature`].
+//!
+//! [`AffinePoint`] converts to and from the SEC1 encodings, with coordinates
+//! of [`EllipticCurve::modulus_bytes`] each: uncompressed 0x04 || X || Y (65
+//! bytes on P-256) and compressed 0x02 || X or 0x03 || X for an even or odd
+//! y (33 bytes). Decompression solves y<sup>2</sup> = x<sup>3</sup> + ax + b
+//! on the PKA and takes the square root with modular exponentiations, which
+//! needs p ≡ 3 mod 4 (the NIST curves, one exponentiation) or p ≡ 5 mod 8
+//! (Wei25519, one or two).
+//!
+//! [`Pka::ecdsa_verify`]: super::Pka::ecdsa_verify
+
+use super::{AffinePoint, EcdsaSignature, EllipticCurve, Error, Pka};
+use crate::uint::Uint;
+
+const SEQUENCE: u8 = 0x30;
+const INTEGER: u8 = 0x02;
+/// First length byte of the two-byte long form, for lengths 128 to 255.
+const LONG_LENGTH_1: u8 = 0x81;
+
+const SEC1_IDENTITY: u8 = 0x00;
+const SEC1_EVEN: u8 = 0x02;
+const SEC1_ODD: u8 = 0x03;
+const SEC1_UNCOMPRESSED: u8 = 0x04;
+
+impl<const MODULUS_SIZE: usize> EcdsaSignature<'_, MODULUS_SIZE> {
+    /// Encodes the signature as r || s into `out` and returns the number of
+    /// bytes written, twice [`EllipticCurve::prime_order_bytes`].
+    ///
+    /// Returns [`Error::Encoding`] if `out` is too short or r or s is wider
+    /// than the curve order.
+    pub fn to_bytes<const PRIME_ORDER_SIZE: usize>(
+        &self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let len = curve.prime_order_bytes();
+        if out.len() < 2 * len {
+            return Err(Error::Encoding);
+        }
+        let (r_out, s_out) = out[..2 * len].split_at_mut(len);
+        self.r_sign.to_be_bytes(r_out).map_err(|_| Error::Encoding)?;
+        self.s_sign.to_be_bytes(s_out).map_err(|_| Error::Encoding)?;
+        Ok(2 * len)
+    }
+
+    /// Decodes r || s, as written by [`EcdsaSignature::to_bytes`], into
+    /// (r, s).
+    ///
+    /// `bytes` must be exactly twice [`EllipticCurve::prime_order_bytes`]
+    /// long.
+    pub fn from_bytes<const PRIME_ORDER_SIZE: usize>(
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        bytes: &[u8],
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        let len = curve.prime_order_bytes();
+        if bytes.len() != 2 * len {
+            return Err(Error::Encoding);
+        }
+        let (r_bytes, s_bytes) = bytes.split_at(len);
+        let r_sign = Uint::from_be_bytes(r_bytes).map_err(|_| Error::Encoding)?;
+        let s_sign = Uint::from_be_bytes(s_bytes).map_err(|_| Error::Encoding)?;
+        Ok((r_sign, s_sign))
+    }
+
+    /// Encodes the signature as DER into `out` and returns the number of
+    /// bytes written.
+    ///
+    /// The encoding takes at most twice [`EllipticCurve::prime_order_bytes`]
+    /// plus 9 bytes (72 on P-256). Returns [`Error::Encoding`] if `out` is
+    /// too short or r or s is wider than the curve order.
+    pub fn to_der<const PRIME_ORDER_SIZE: usize>(
+        &self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        let max_bits = 8 * curve.prime_order_bytes();
+        if self.r_sign.bits() > max_bits || self.s_sign.bits() > max_bits {
+            return Err(Error::Encoding);
+        }
+        let r_len = integer_len(self.r_sign);
+        let s_len = integer_len(self.s_sign);
+        let content_len = 2 + r_len + 2 + s_len;
+        let header_len = if content_len < 0x80 { 2 } else { 3 };
+        if content_len > 0xff || out.len() < header_len + content_len {
+            return Err(Error::Encoding);
+        }
+
+        out[0] = SEQUENCE;
+        if header_len == 2 {
+            out[1] = content_len as u8;
+        } else {
+            out[1] = LONG_LENGTH_1;
+            out[2] = content_len as u8;
+        }
+        let s_start = header_len + 2 + r_len;
+        write_integer(self.r_sign, &mut out[header_len..s_start])?;
+        write_integer(self.s_sign, &mut out[s_start..header_len + content_len])?;
+        Ok(header_len + content_len)
+    }
+
+    /// Decodes a DER signature into (r, s), rejecting any encoding that is
+    /// not the unique DER one.
+    ///
+    /// Returns [`Error::Encoding`] if the encoding is malformed, is followed
+    /// by other bytes, or holds an r or s wider than the curve order.
+    pub fn from_der<const PRIME_ORDER_SIZE: usize>(
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        der: &[u8],
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        let (content_len, content) = match der {
+            [SEQUENCE, len, content @ ..] if *len < 0x80 => (*len as usize, content),
+            [SEQUENCE, LONG_LENGTH_1, len, content @ ..] if *len >= 0x80 => {
+                (*len as usize, content)
+            }
+            _ => return Err(Error::Encoding),
+        };
+        if content.len() != content_len {
+            return Err(Error::Encoding);
+        }
+
+        let max_len = curve.prime_order_bytes();
+        let (r_sign, rest) = read_integer(content, max_len)?;
+        let (s_sign, rest) = read_integer(rest, max_len)?;
+        if !rest.is_empty() {
+            return Err(Error::Encoding);
+        }
+        Ok((r_sign, s_sign))
+    }
+}
+
+impl<const MODULUS_SIZE: usize> AffinePoint<MODULUS_SIZE> {
+    /// Encodes the point in SEC1 form into `out` and returns the number of
+    /// bytes written: 0x04 || X || Y, or 0x02 / 0x03 || X with `compress`.
+    ///
+    /// Returns [`Error::PointAtInfinity`] for [`AffinePoint::IDENTITY`], and
+    /// [`Error::Encoding`] if `out` is too short or a coordinate is wider
+    /// than the modulus.
+    pub fn to_sec1_bytes<const PRIME_ORDER_SIZE: usize>(
+        &self,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        compress: bool,
+        out: &mut [u8],
+    ) -> Result<usize, Error> {
+        if self.is_identity() {
+            return Err(Error::PointAtInfinity);
+        }
+        let len = curve.modulus_bytes();
+        let total = if compress { 1 + len } else { 1 + 2 * len };
+        if out.len() < total {
+            return Err(Error::Encoding);
+        }
+
+        self.x.to_be_bytes(&mut out[1..1 + len]).map_err(|_| Error::Encoding)?;
+        if compress {
+            out[0] = if self.y.bit(0) { SEC1_ODD } else { SEC1_EVEN };
+        } else {
+            out[0] = SEC1_UNCOMPRESSED;
+            self.y.to_be_bytes(&mut out[1 + len..total]).map_err(|_| Error::Encoding)?;
+        }
+        Ok(total)
+    }
+
+    /// Decodes a SEC1 point, compressed or uncompressed, and checks that it
+    /// is on `curve`.
+    ///
+    /// Uncompressed points go through [`Pka::validate_public_key`];
+    /// compressed ones get the y whose square is x<sup>3</sup> + ax + b and
+    /// whose parity matches the prefix. Returns
+    /// [`Error::PointAtInfinity`] for the one-byte encoding 0x00,
+    /// [`Error::OutOfRange`] if a coordinate is not smaller than p,
+    /// [`Error::NotOnCurve`] if the point is not on the curve or x has no y,
+    /// and [`Error::Encoding`] for a bad prefix or length, or a compressed
+    /// point on a curve whose p is 1 mod 8.
+    pub fn from_sec1_bytes<const PRIME_ORDER_SIZE: usize>(
+        pka: &mut Pka,
+        curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        bytes: &[u8],
+    ) -> Result<Self, Error> {
+        let len = curve.modulus_bytes();
+        let coordinate = |bytes: &[u8]| Uint::from_be_bytes(bytes).map_err(|_| Error::Encoding);
+        match bytes {
+            [SEC1_IDENTITY] => Err(Error::PointAtInfinity),
+            [SEC1_UNCOMPRESSED, coordinates @ ..] if coordinates.len() == 2 * len => {
+                let (x, y) = coordinates.split_at(len);
+                let point = Self::new(coordinate(x)?, coordinate(y)?);
+                pka.validate_public_key(curve, &(&point).into())?;
+                Ok(point)
+            }
+            [prefix @ (SEC1_EVEN | SEC1_ODD), x @ ..] if x.len() == len => {
+                let x = coordinate(x)?;
+                if x >= curve.modulus {
+                    return Err(Error::OutOfRange);
+                }
+                let y = curve_y(pka, curve, &x, *prefix == SEC1_ODD)?;
+                Ok(Self::new(x, y))
+            }
+            _ => Err(Error::Encoding),
+        }
+    }
+}
+
+/// The y of parity `odd` with y<sup>2</sup> = x<sup>3</sup> + ax + b.
+fn curve_y<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+    pka: &mut Pka,
+    curve: &EllipticCurve<MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    x: &Uint<MODULUS_SIZE>,
+    odd: bool,
+) -> Result<Uint<MODULUS_SIZE>, Error> {
+    let p = &curve.modulus;
+    let x2 = pka.mod_mul(x, x, p)?;
+    let x2_plus_a = pka.mod_add(&x2, &curve.coef_a_mod_p(), p)?;
+    let x3_plus_ax = pka.mod_mul(&x2_plus_a, x, p)?;
+    let rhs = pka.mod_add(&x3_plus_ax, &curve.coef_b, p)?;
+
+    let y = sqrt(pka, &rhs, p)?;
+    if y.bit(0) == odd {
+        Ok(y)
+    } else if y.is_zero() {
+        // 0 has no odd root
+        Err(Error::Encoding)
+    } else {
+        Ok(y.neg_mod(p))
+    }
+}
+
+/// A square root of `a` modulo the prime `p`, with exponentiations on the
+/// PKA.
+///
+/// For p ≡ 3 mod 4 it is a<sup>(p + 1) / 4</sup>, one exponentiation. For
+/// p ≡ 5 mod 8, y = a<sup>(p + 3) / 8</sup> is a root of a or of -a, and in
+/// the latter case a second exponentiation gives 2<sup>(p - 1) / 4</sup>,
+/// and y * 2<sup>(p - 1) / 4</sup> is the root of a. Returns
+/// [`Error::NotOnCurve`] if `a` is not a square.
+fn sqrt<const LIMBS: usize>(
+    pka: &mut Pka,
+    a: &Uint<LIMBS>,
+    p: &Uint<LIMBS>,
+) -> Result<Uint<LIMBS>, Error> {
+    if a.is_zero() {
+        return Ok(Uint::ZERO);
+    }
+    let y = match p.as_le_words()[0] & 0b111 {
+        0b011 | 0b111 => pka.mod_exp(a, &p.shr(2).adc(&Uint::ONE).0, p)?,
+        0b101 => {
+            let y = pka.mod_exp(a, &p.shr(3).adc(&Uint::ONE).0, p)?;
+            if pka.mod_mul(&y, &y, p)? == *a {
+                y
+            } else {
+                let sqrt_m1 = pka.mod_exp(&Uint::from_u32(2), &p.shr(2), p)?;
+                pka.mod_mul(&y, &sqrt_m1, p)?
+            }
+        }
+        _ => return Err(Error::Encoding),
+    };
+    if pka.mod_mul(&y, &y, p)? != *a {
+        return Err(Error::NotOnCurve);
+    }
+    Ok(y)
+}
+
+/// Length of the DER INTEGER content of `value`: its minimal big-endian
+/// bytes, plus a zero byte if the top bit is set so it stays positive.
+fn integer_len<const LIMBS: usize>(value: &Uint<LIMBS>) -> usize {
+    value.bits() / 8 + 1
+}
+
+/// Writes `value` as a DER INTEGER filling `out`, sized with
+/// [`integer_len`].
+fn write_integer<const LIMBS: usize>(value: &Uint<LIMBS>, out: &mut [u8]) -> Result<(), Error> {
+    out[0] = INTEGER;
+    out[1] = (out.len() - 2) as u8;
+    // Left padding supplies the zero byte in front of a set top bit
+    value.to_be_bytes(&mut out[2..]).map_err(|_| Error::Encoding)
+}
+
+/// Reads a DER INTEGER of at most `max_len` value bytes from the start of
+/// `der`, and returns it with the bytes that follow.
+fn read_integer<const LIMBS: usize>(
+    der: &[u8],
+    max_len: usize,
+) -> Result<(Uint<LIMBS>, &[u8]), Error> {
+    let [INTEGER, len, rest @ ..] = der else {
+        return Err(Error::Encoding);
+    };
+    // Integers that fit the PKA never need the long length form
+    let len = *len as usize;
+    if len == 0 || len >= 0x80 || rest.len() < len {
+        return Err(Error::Encoding);
+    }
+    let (content, rest) = rest.split_at(len);
+
+    let value = match content {
+        // Negative
+        [first, ..] if first & 0x80 != 0 => return Err(Error::Encoding),
+        // Zero byte not needed to clear the sign bit
+        [0, second, ..] if second & 0x80 == 0 => return Err(Error::Encoding),
+        [0, value @ ..] => value,
+        value => value,
+    };
+    if value.len() > max_len {
+        return Err(Error::Encoding);
+    }
+    let value = Uint::from_be_bytes(value).map_err(|_| Error::Encoding)?;
+    Ok((value, rest))
+}
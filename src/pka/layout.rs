@@ -0,0 +1,378 @@
+//! PKA RAM layout of each operation (RM0493, PKA operating modes).
+//!
+//! Every field an operation reads or writes is declared once here, with its
+//! offset from the PKA base and the room the reference manual reserves for
+//! it. The drivers take their addresses from these tables, and a
+//! compile-time check rejects a layout whose inputs overlap or run past the
+//! end of the PKA RAM.
+//!
+//! A [`Field`] can only be built here, so the driver's accessors trust its
+//! address and bound every access by its size.
+
+use super::{BASE, PKA_RAM_OFFSET, RAM_NUM_DW};
+
+/// Size of a PKA RAM doubleword, in bytes.
+const DW: usize = 8;
+
+/// Largest ECC operand: modulus, coefficients, coordinates and scalars.
+const ECC_MAX_BITS: usize = 640;
+
+/// Largest operand of the modular arithmetic and exponentiation operations.
+const ARITH_MAX_BITS: usize = 4160;
+
+/// A field of the PKA RAM.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Field {
+    /// Offset from the PKA base.
+    offset: usize,
+    /// Largest value, in 32-bit words.
+    max_words: usize,
+    /// Bytes reserved for the field, trailing zero doubleword included.
+    size: usize,
+}
+
+impl Field {
+    /// A 32-bit parameter (operand length, sign, result code) in a
+    /// doubleword of its own.
+    const fn word(offset: usize) -> Self {
+        Self {
+            offset,
+            max_words: 1,
+            size: DW,
+        }
+    }
+
+    /// An operand of at most `max_bits` bits, followed by the zero
+    /// doubleword the PKA expects after it.
+    const fn operand(offset: usize, max_bits: usize) -> Self {
+        Self {
+            offset,
+            max_words: max_bits.div_ceil(64) * 2,
+            size: (max_bits.div_ceil(64) + 1) * DW,
+        }
+    }
+
+    const fn ecc(offset: usize) -> Self {
+        Self::operand(offset, ECC_MAX_BITS)
+    }
+
+    const fn arith(offset: usize) -> Self {
+        Self::operand(offset, ARITH_MAX_BITS)
+    }
+
+    /// A prime factor of an RSA modulus, or a CRT parameter.
+    const fn half(offset: usize) -> Self {
+        Self::operand(offset, ARITH_MAX_BITS / 2)
+    }
+
+    /// Address of the first word of the field.
+    pub(crate) const fn address(self) -> usize {
+        BASE + self.offset
+    }
+
+    /// Number of 32-bit words the field can hold, zero doubleword excluded.
+    pub(crate) const fn max_words(self) -> usize {
+        self.max_words
+    }
+
+    /// Offset one past the last byte of the field.
+    const fn end(self) -> usize {
+        self.offset + self.size
+    }
+
+    const fn overlaps(self, other: Field) -> bool {
+        self.offset < other.end() && other.offset < self.end()
+    }
+
+    const fn in_ram(self) -> bool {
+        self.offset.is_multiple_of(DW)
+            && self.offset >= PKA_RAM_OFFSET
+            && self.end() <= PKA_RAM_OFFSET + RAM_NUM_DW * DW
+    }
+}
+
+/// Declares the input and output fields of each operation, as constants of
+/// a module named after it, and the table checked by [`check`].
+macro_rules! layouts {
+    ($(
+        $(#[$doc:meta])*
+        $op:ident {
+            inputs { $($input:ident: $input_field:expr,)* }
+            outputs { $($output:ident: $output_field:expr,)* }
+        }
+    )*) => {
+        $(
+            $(#[$doc])*
+            pub(crate) mod $op {
+                use super::Field;
+
+                $(pub(crate) const $input: Field = $input_field;)*
+                $(pub(crate) const $output: Field = $output_field;)*
+
+                pub(super) const INPUTS: &[Field] = &[$($input),*];
+                pub(super) const OUTPUTS: &[Field] = &[$($output),*];
+            }
+        )*
+
+        /// Inputs and outputs of every operation.
+        const LAYOUTS: &[(&[Field], &[Field])] = &[$(($op::INPUTS, $op::OUTPUTS)),*];
+    };
+}
+
+layouts! {
+    /// Montgomery parameter computation.
+    montgomery {
+        inputs {
+            P_LEN: Field::word(0x408),
+            P: Field::arith(0x1088),
+        }
+        outputs {
+            OUT: Field::arith(0x620),
+        }
+    }
+
+    /// Modular addition, subtraction and Montgomery multiplication.
+    mod_op {
+        inputs {
+            LEN: Field::word(0x408),
+            A: Field::arith(0xA50),
+            B: Field::arith(0xC68),
+            MODULUS: Field::arith(0x1088),
+        }
+        outputs {
+            OUT: Field::arith(0xE78),
+        }
+    }
+
+    /// Modular inversion.
+    mod_inv {
+        inputs {
+            LEN: Field::word(0x408),
+            A: Field::arith(0xA50),
+            MODULUS: Field::arith(0xC68),
+        }
+        outputs {
+            OUT: Field::arith(0xE78),
+        }
+    }
+
+    /// Modular reduction.
+    mod_red {
+        inputs {
+            OPERAND_LEN: Field::word(0x400),
+            MODULUS_LEN: Field::word(0x408),
+            A: Field::arith(0xA50),
+            MODULUS: Field::arith(0xC68),
+        }
+        outputs {
+            OUT: Field::arith(0xE78),
+        }
+    }
+
+    /// Modular exponentiation, Montgomery parameter included.
+    mod_exp {
+        inputs {
+            EXPONENT_LEN: Field::word(0x400),
+            OPERAND_LEN: Field::word(0x408),
+            BASE: Field::arith(0xC68),
+            EXPONENT: Field::arith(0xE78),
+            MODULUS: Field::arith(0x1088),
+        }
+        outputs {
+            OUT: Field::arith(0x838),
+        }
+    }
+
+    /// RSA CRT exponentiation.
+    rsa_crt {
+        inputs {
+            LEN: Field::word(0x408),
+            DP: Field::half(0x730),
+            QINV: Field::half(0x948),
+            P: Field::half(0xB60),
+            DQ: Field::half(0xE78),
+            Q: Field::half(0x1088),
+            BASE: Field::arith(0x12A0),
+        }
+        outputs {
+            OUT: Field::arith(0x838),
+        }
+    }
+
+    /// ECC scalar multiplication.
+    ecc_mul {
+        inputs {
+            N_LEN: Field::word(0x400),
+            P_LEN: Field::word(0x408),
+            A_SIGN: Field::word(0x410),
+            A: Field::ecc(0x418),
+            B: Field::ecc(0x520),
+            P: Field::ecc(0x1088),
+            K: Field::ecc(0x12A0),
+            X: Field::ecc(0x578),
+            Y: Field::ecc(0x470),
+            N: Field::ecc(0xF88),
+        }
+        outputs {
+            OUT_X: Field::ecc(0x578),
+            OUT_Y: Field::ecc(0x5D0),
+            OUT_ERROR: Field::word(0x680),
+        }
+    }
+
+    /// Point on elliptic curve check.
+    point_check {
+        inputs {
+            P_LEN: Field::word(0x408),
+            A_SIGN: Field::word(0x410),
+            A: Field::ecc(0x418),
+            B: Field::ecc(0x520),
+            P: Field::ecc(0x470),
+            X: Field::ecc(0x578),
+            Y: Field::ecc(0x5D0),
+            MONTGOMERY: Field::ecc(0x4C8),
+        }
+        outputs {
+            OUT: Field::word(0x680),
+        }
+    }
+
+    /// ECC complete addition of projective points.
+    ecc_add {
+        inputs {
+            P_LEN: Field::word(0x408),
+            A_SIGN: Field::word(0x410),
+            A: Field::ecc(0x418),
+            B: Field::ecc(0x520),
+            P: Field::ecc(0x470),
+            PX: Field::ecc(0x628),
+            PY: Field::ecc(0x680),
+            PZ: Field::ecc(0x6D8),
+            QX: Field::ecc(0x730),
+            QY: Field::ecc(0x788),
+            QZ: Field::ecc(0x7E0),
+        }
+        outputs {
+            OUT_X: Field::ecc(0xD60),
+            OUT_Y: Field::ecc(0xDB8),
+            OUT_Z: Field::ecc(0xE10),
+        }
+    }
+
+    /// ECC double base ladder, k * P + m * Q.
+    double_ladder {
+        inputs {
+            N_LEN: Field::word(0x400),
+            P_LEN: Field::word(0x408),
+            A_SIGN: Field::word(0x410),
+            A: Field::ecc(0x418),
+            P: Field::ecc(0x470),
+            K: Field::ecc(0x520),
+            M: Field::ecc(0x578),
+            PX: Field::ecc(0x628),
+            PY: Field::ecc(0x680),
+            PZ: Field::ecc(0x6D8),
+            QX: Field::ecc(0x730),
+            QY: Field::ecc(0x788),
+            QZ: Field::ecc(0x7E0),
+        }
+        outputs {
+            OUT_X: Field::ecc(0x578),
+            OUT_Y: Field::ecc(0x5D0),
+            OUT_ERROR: Field::word(0x520),
+        }
+    }
+
+    /// ECC projective to affine conversion.
+    to_affine {
+        inputs {
+            P_LEN: Field::word(0x408),
+            P: Field::ecc(0x470),
+            X: Field::ecc(0xD60),
+            Y: Field::ecc(0xDB8),
+            Z: Field::ecc(0xE10),
+            MONTGOMERY: Field::ecc(0x4C8),
+        }
+        outputs {
+            OUT_X: Field::ecc(0x578),
+            OUT_Y: Field::ecc(0x5D0),
+            OUT_ERROR: Field::word(0x680),
+        }
+    }
+
+    /// ECDSA signature generation.
+    ecdsa_sign {
+        inputs {
+            N_LEN: Field::word(0x400),
+            P_LEN: Field::word(0x408),
+            A_SIGN: Field::word(0x410),
+            A: Field::ecc(0x418),
+            B: Field::ecc(0x520),
+            P: Field::ecc(0x1088),
+            K: Field::ecc(0x12A0),
+            X: Field::ecc(0x578),
+            Y: Field::ecc(0x470),
+            Z: Field::ecc(0xFE8),
+            D: Field::ecc(0xF28),
+            N: Field::ecc(0xF88),
+        }
+        outputs {
+            OUT_R: Field::ecc(0x730),
+            OUT_S: Field::ecc(0x788),
+            OUT_RESULT: Field::word(0xFE0),
+        }
+    }
+
+    /// ECDSA signature verification.
+    ecdsa_verify {
+        inputs {
+            N_LEN: Field::word(0x408),
+            P_LEN: Field::word(0x4C8),
+            A_SIGN: Field::word(0x468),
+            A: Field::ecc(0x470),
+            P: Field::ecc(0x4D0),
+            X: Field::ecc(0x678),
+            Y: Field::ecc(0x6D0),
+            XQ: Field::ecc(0x12F8),
+            YQ: Field::ecc(0x1350),
+            R: Field::ecc(0x10E0),
+            S: Field::ecc(0xC68),
+            Z: Field::ecc(0x13A8),
+            N: Field::ecc(0x1088),
+        }
+        outputs {
+            OUT: Field::word(0x5D0),
+        }
+    }
+}
+
+/// Panics, at compile time, if a field is misaligned or outside the PKA RAM,
+/// or if two inputs of an operation overlap.
+///
+/// This stands in for a host test: the crate has no test harness, and a
+/// `const` evaluation runs on every build, for the target as well as the
+/// host, so a broken table can never reach a board.
+const fn check(layouts: &[(&[Field], &[Field])]) {
+    let mut op = 0;
+    while op < layouts.len() {
+        let (inputs, outputs) = layouts[op];
+        let mut i = 0;
+        while i < inputs.len() {
+            assert!(inputs[i].in_ram(), "PKA input field outside the PKA RAM");
+            let mut j = i + 1;
+            while j < inputs.len() {
+                assert!(!inputs[i].overlaps(inputs[j]), "overlapping PKA input fields");
+                j += 1;
+            }
+            i += 1;
+        }
+        let mut o = 0;
+        while o < outputs.len() {
+            assert!(outputs[o].in_ram(), "PKA output field outside the PKA RAM");
+            o += 1;
+        }
+        op += 1;
+    }
+}
+
+const _: () = check(LAYOUTS);
@@ -0,0 +1,139 @@
+//! RustCrypto `signature` traits for ECDSA over NIST P-256.
+//!
+//! [`P256SigningKey`] implements [`Signer`] and [`RandomizedSigner`], and
+//! [`P256VerifyingKey`] implements [`Verifier`], for
+//! [`p256::ecdsa::Signature`](Signature), so code written against the `p256`
+//! crate can sign and verify on the PKA. Messages are hashed with SHA-256, as
+//! `p256::ecdsa` does.
+//!
+//! The traits take the keys by shared reference, so the keys borrow the
+//! driver through a [`RefCell`]. An operation fails with a
+//! [`signature::Error`] if the driver is already borrowed.
+
+use core::cell::RefCell;
+
+use ::p256::{ecdsa::Signature, FieldBytes};
+use rand_core::{CryptoRng, CryptoRngCore, RngCore};
+use sha2::{Digest, Sha256};
+use signature::{RandomizedSigner, Signer, Verifier};
+
+use super::{curve::NIST_P256, Error, Pka, SigningKey, VerifyingKey};
+use crate::uint::Uint;
+
+/// SHA-256 of `msg`, as signed by the PKA.
+fn hash(msg: &[u8]) -> Uint<8> {
+    Uint::from_be_bytes(&Sha256::digest(msg)).expect("SHA-256 digest is 32 bytes")
+}
+
+/// ECDSA P-256 private key on the PKA.
+///
+/// Nonces for [`Signer`] are drawn from the `rng` given at construction.
+pub struct P256SigningKey<'p, R> {
+    pka: &'p RefCell<Pka>,
+    rng: RefCell<R>,
+    key: SigningKey<'static, 8, 8>,
+}
+
+impl<'p, R: RngCore + CryptoRng> P256SigningKey<'p, R> {
+    /// Generates a new key pair; see [`SigningKey::generate`].
+    pub fn generate(pka: &'p RefCell<Pka>, mut rng: R) -> Result<Self, Error> {
+        let key = SigningKey::generate(&mut pka.borrow_mut(), &mut rng, &NIST_P256)?;
+        Ok(Self {
+            pka,
+            rng: RefCell::new(rng),
+            key,
+        })
+    }
+
+    /// Loads a private key from its 32-byte big-endian encoding, as
+    /// `p256::ecdsa::SigningKey::from_bytes` does.
+    ///
+    /// Returns [`Error::InvalidScalar`] unless the key is in [1, n - 1].
+    pub fn from_bytes(pka: &'p RefCell<Pka>, rng: R, bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 32 {
+            return Err(Error::InvalidScalar);
+        }
+        let secret = Uint::from_be_bytes(bytes).map_err(|_| Error::InvalidScalar)?;
+        let key = SigningKey::from_scalar(&mut pka.borrow_mut(), &NIST_P256, secret)?;
+        Ok(Self {
+            pka,
+            rng: RefCell::new(rng),
+            key,
+        })
+    }
+
+    /// Public key matching this private key.
+    pub fn verifying_key(&self) -> P256VerifyingKey<'p> {
+        P256VerifyingKey {
+            pka: self.pka,
+            key: self.key.verifying_key(),
+        }
+    }
+
+    fn sign_with<G: RngCore + CryptoRng>(
+        &self,
+        rng: &mut G,
+        msg: &[u8],
+    ) -> Result<Signature, signature::Error> {
+        let mut pka = self.pka.try_borrow_mut().map_err(|_| signature::Error::new())?;
+        let (r_sign, s_sign) = self
+            .key
+            .sign(&mut pka, rng, &hash(msg))
+            .map_err(|_| signature::Error::new())?;
+
+        let mut r_bytes = FieldBytes::default();
+        let mut s_bytes = FieldBytes::default();
+        r_sign.to_be_bytes(&mut r_bytes).map_err(|_| signature::Error::new())?;
+        s_sign.to_be_bytes(&mut s_bytes).map_err(|_| signature::Error::new())?;
+        Signature::from_scalars(r_bytes, s_bytes)
+    }
+}
+
+impl<R: RngCore + CryptoRng> Signer<Signature> for P256SigningKey<'_, R> {
+    fn try_sign(&self, msg: &[u8]) -> Result<Signature, signature::Error> {
+        let mut rng = self.rng.try_borrow_mut().map_err(|_| signature::Error::new())?;
+        self.sign_with(&mut *rng, msg)
+    }
+}
+
+impl<R: RngCore + CryptoRng> RandomizedSigner<Signature> for P256SigningKey<'_, R> {
+    fn try_sign_with_rng(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        msg: &[u8],
+    ) -> Result<Signature, signature::Error> {
+        self.sign_with(rng, msg)
+    }
+}
+
+/// ECDSA P-256 public key on the PKA.
+#[derive(Clone, Copy)]
+pub struct P256VerifyingKey<'p> {
+    pka: &'p RefCell<Pka>,
+    key: VerifyingKey<'static, 8, 8>,
+}
+
+impl<'p> P256VerifyingKey<'p> {
+    /// Wraps a P-256 public key.
+    pub fn new(pka: &'p RefCell<Pka>, key: VerifyingKey<'static, 8, 8>) -> Self {
+        Self { pka, key }
+    }
+
+    /// Public key as used by [`VerifyingKey::verify`].
+    pub fn as_verifying_key(&self) -> &VerifyingKey<'static, 8, 8> {
+        &self.key
+    }
+}
+
+impl Verifier<Signature> for P256VerifyingKey<'_> {
+    fn verify(&self, msg: &[u8], signature: &Signature) -> Result<(), signature::Error> {
+        let (r_bytes, s_bytes) = signature.split_bytes();
+        let r_sign = Uint::from_be_bytes(&r_bytes).map_err(|_| signature::Error::new())?;
+        let s_sign = Uint::from_be_bytes(&s_bytes).map_err(|_| signature::Error::new())?;
+
+        let mut pka = self.pka.try_borrow_mut().map_err(|_| signature::Error::new())?;
+        self.key
+            .verify(&mut pka, &r_sign, &s_sign, &hash(msg))
+            .map_err(|_| signature::Error::new())
+    }
+}
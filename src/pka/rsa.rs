@@ -0,0 +1,160 @@
+//! RSA private key operations in CRT form.
+
+use zeroize::{Zeroize, ZeroizeOnDrop};
+#[cfg(feature = "blinding")]
+use zeroize::Zeroizing;
+
+use super::{layout::rsa_crt, Error, Fault, Pka, PkaOpcode};
+use crate::uint::Uint;
+
+/// RSA private key in CRT form, for [`Pka::rsa_crt_sign`].
+///
+/// The modulus n = p * q fits in `LIMBS` words, and each of the primes and
+/// CRT parameters in `HALF_LIMBS`. The secret parts are wiped when the key is
+/// dropped.
+pub struct RsaCrtKey<const LIMBS: usize, const HALF_LIMBS: usize> {
+    modulus: Uint<LIMBS>,
+    public_exponent: u32,
+    p: Uint<HALF_LIMBS>,
+    q: Uint<HALF_LIMBS>,
+    dp: Uint<HALF_LIMBS>,
+    dq: Uint<HALF_LIMBS>,
+    qinv: Uint<HALF_LIMBS>,
+}
+
+impl<const LIMBS: usize, const HALF_LIMBS: usize> RsaCrtKey<LIMBS, HALF_LIMBS> {
+    /// Loads a key from n, e, the primes p and q, d<sub>p</sub> = d mod
+    /// (p - 1), d<sub>q</sub> = d mod (q - 1) and q<sub>inv</sub> =
+    /// q<sup>-1</sup> mod p.
+    pub fn new(
+        modulus: Uint<LIMBS>,
+        public_exponent: u32,
+        p: Uint<HALF_LIMBS>,
+        q: Uint<HALF_LIMBS>,
+        dp: Uint<HALF_LIMBS>,
+        dq: Uint<HALF_LIMBS>,
+        qinv: Uint<HALF_LIMBS>,
+    ) -> Self {
+        Self {
+            modulus,
+            public_exponent,
+            p,
+            q,
+            dp,
+            dq,
+            qinv,
+        }
+    }
+
+    /// Modulus n.
+    pub fn modulus(&self) -> &Uint<LIMBS> {
+        &self.modulus
+    }
+
+    /// Public exponent e.
+    pub fn public_exponent(&self) -> u32 {
+        self.public_exponent
+    }
+}
+
+impl<const LIMBS: usize, const HALF_LIMBS: usize> Zeroize for RsaCrtKey<LIMBS, HALF_LIMBS> {
+    fn zeroize(&mut self) {
+        self.p.zeroize();
+        self.q.zeroize();
+        self.dp.zeroize();
+        self.dq.zeroize();
+        self.qinv.zeroize();
+    }
+}
+
+impl<const LIMBS: usize, const HALF_LIMBS: usize> Drop for RsaCrtKey<LIMBS, HALF_LIMBS> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const LIMBS: usize, const HALF_LIMBS: usize> ZeroizeOnDrop for RsaCrtKey<LIMBS, HALF_LIMBS> {}
+
+impl Pka {
+    /// Computes message<sup>d</sup> mod n with the PKA RSA CRT
+    /// exponentiation.
+    ///
+    /// With the `blinding` feature the exponentiation runs on
+    /// message * r<sup>e</sup> for a fresh random r, and the result is
+    /// multiplied by r<sup>-1</sup>, as in [`Pka::rsa_private`].
+    ///
+    /// With [`Pka::set_verify_after_sign`] the result is raised to e and
+    /// compared with `message` before it is returned.
+    pub fn rsa_crt_sign<const LIMBS: usize, const HALF_LIMBS: usize>(
+        &mut self,
+        key: &RsaCrtKey<LIMBS, HALF_LIMBS>,
+        message: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        if *message >= key.modulus {
+            return Err(Error::OutOfRange);
+        }
+
+        #[cfg(not(feature = "blinding"))]
+        let mut result = self.rsa_crt(key, message)?;
+
+        #[cfg(feature = "blinding")]
+        let mut result = {
+            let (blind, unblind) = self.rsa_blinding(key.public_exponent, &key.modulus)?;
+            let blinded = Zeroizing::new(self.mod_mul(message, &blind, &key.modulus)?);
+            let result = Zeroizing::new(self.rsa_crt(key, &blinded)?);
+            self.mod_mul(&result, &unblind, &key.modulus)?
+        };
+
+        if self.verify_after_sign {
+            self.check_rsa_signature(&mut result, message, key.public_exponent, &key.modulus)?;
+        }
+        Ok(result)
+    }
+
+    /// Runs the PKA RSA CRT exponentiation of `base`.
+    fn rsa_crt<const LIMBS: usize, const HALF_LIMBS: usize>(
+        &mut self,
+        key: &RsaCrtKey<LIMBS, HALF_LIMBS>,
+        base: &Uint<LIMBS>,
+    ) -> Result<Uint<LIMBS>, Error> {
+        self.clear_all_flags();
+        self.zero_ram();
+
+        self.write_word(rsa_crt::LEN, key.modulus.bits() as u32);
+        self.write_uint(rsa_crt::DP, &key.dp)?;
+        self.write_uint(rsa_crt::DQ, &key.dq)?;
+        self.write_uint(rsa_crt::QINV, &key.qinv)?;
+        self.write_uint(rsa_crt::P, &key.p)?;
+        self.write_uint(rsa_crt::Q, &key.q)?;
+        self.write_uint(rsa_crt::BASE, base)?;
+
+        self.start(PkaOpcode::RsaCrt)?;
+        self.finish(PkaOpcode::RsaCrt)?;
+        self.fault_point(Fault::RsaCrt);
+        Ok(self.take_uint(rsa_crt::OUT, key.modulus.bits() as u32))
+    }
+
+    /// Checks that signature<sup>e</sup> mod n gives back `message`, the
+    /// check run by [`Pka::set_verify_after_sign`].
+    ///
+    /// If it does not, `signature` is wiped along with the PKA RAM and
+    /// [`Error::FaultDetected`] is returned.
+    pub fn check_rsa_signature<const LIMBS: usize>(
+        &mut self,
+        signature: &mut Uint<LIMBS>,
+        message: &Uint<LIMBS>,
+        public_exponent: u32,
+        modulus: &Uint<LIMBS>,
+    ) -> Result<(), Error> {
+        let exponent = Uint::<1>::from_u32(public_exponent);
+        let recovered = self.mod_exp(signature, &exponent, modulus);
+        if recovered.as_ref() == Ok(message) {
+            return Ok(());
+        }
+        signature.zeroize();
+        match recovered {
+            Err(Error::Timeout) => Err(Error::Timeout),
+            _ => self.fail(Error::FaultDetected),
+        }
+    }
+}
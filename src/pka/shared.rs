@@ -0,0 +1,110 @@
+//! PKA shared between tasks.
+//!
+//! An operation spreads over several steps: operands are written to the PKA
+//! RAM, the PKA runs, and the results are read back; the protected scalar
+//! multiplication and the ECDSA nonce retries even chain several PKA runs.
+//! [`SharedPka`] keeps the driver behind an async mutex and holds the lock
+//! for a whole operation, so a task never writes its operands over those of
+//! another one or reads back a result that is not its own.
+
+use embassy_sync::{
+    blocking_mutex::raw::RawMutex,
+    mutex::{Mutex, MutexGuard},
+};
+use rand_core::{CryptoRng, RngCore};
+
+use super::{AffinePoint, EphemeralSecret, Error, Pka, SharedSecret, SigningKey, VerifyingKey};
+use crate::uint::Uint;
+
+/// Exclusive access to a [`SharedPka`], released when dropped.
+pub type PkaGuard<'a, M> = MutexGuard<'a, M, Pka>;
+
+/// PKA driver shared between tasks.
+///
+/// Use [`CriticalSectionRawMutex`] for `M` when the tasks run on different
+/// executors, [`ThreadModeRawMutex`] when they all run in thread mode.
+///
+/// The operation methods lock the PKA for their whole duration. To chain
+/// several operations without another task getting in between, take the
+/// lock with [`SharedPka::lock`] and call the `_async` methods of [`Pka`] on
+/// the guard.
+///
+/// [`CriticalSectionRawMutex`]: embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex
+/// [`ThreadModeRawMutex`]: embassy_sync::blocking_mutex::raw::ThreadModeRawMutex
+pub struct SharedPka<M: RawMutex> {
+    pka: Mutex<M, Pka>,
+}
+
+impl<M: RawMutex> SharedPka<M> {
+    pub const fn new(pka: Pka) -> Self {
+        Self {
+            pka: Mutex::new(pka),
+        }
+    }
+
+    /// Waits for the PKA to be free and locks it.
+    ///
+    /// If the previous owner dropped an operation future halfway, the PKA
+    /// may still be running; the lock is only handed over once that
+    /// operation has ended.
+    pub async fn lock(&self) -> PkaGuard<'_, M> {
+        let mut pka = self.pka.lock().await;
+        pka.settle_async().await;
+        pka
+    }
+
+    /// Signs `hash` with `key`; see [`SigningKey::sign`].
+    ///
+    /// The lock is held across the nonce retries.
+    pub async fn sign<
+        R: RngCore + CryptoRng,
+        const MODULUS_SIZE: usize,
+        const PRIME_ORDER_SIZE: usize,
+    >(
+        &self,
+        key: &SigningKey<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        rng: &mut R,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(Uint<MODULUS_SIZE>, Uint<MODULUS_SIZE>), Error> {
+        key.sign_async(&mut *self.lock().await, rng, hash).await
+    }
+
+    /// Verifies the signature (`r_sign`, `s_sign`) of `hash`; see
+    /// [`VerifyingKey::verify`].
+    pub async fn verify<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &self,
+        key: &VerifyingKey<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        r_sign: &Uint<MODULUS_SIZE>,
+        s_sign: &Uint<MODULUS_SIZE>,
+        hash: &Uint<PRIME_ORDER_SIZE>,
+    ) -> Result<(), Error> {
+        key.verify_async(&mut *self.lock().await, r_sign, s_sign, hash)
+            .await
+    }
+
+    /// Computes the ECDH public key of `secret`; see
+    /// [`EphemeralSecret::public_key`].
+    pub async fn public_key<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &self,
+        secret: &EphemeralSecret<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>,
+    ) -> Result<AffinePoint<MODULUS_SIZE>, Error> {
+        secret.public_key_async(&mut *self.lock().await).await
+    }
+
+    /// Computes the ECDH shared secret with `peer`; see
+    /// [`EphemeralSecret::diffie_hellman`].
+    pub async fn diffie_hellman<const MODULUS_SIZE: usize, const PRIME_ORDER_SIZE: usize>(
+        &self,
+        secret: &EphemeralSecret<'_, MODULUS_SIZE, PRIME_ORDER_SIZE>,
+        peer: &AffinePoint<MODULUS_SIZE>,
+    ) -> Result<SharedSecret<MODULUS_SIZE>, Error> {
+        secret
+            .diffie_hellman_async(&mut *self.lock().await, peer)
+            .await
+    }
+
+    /// Returns the driver.
+    pub fn into_inner(self) -> Pka {
+        self.pka.into_inner()
+    }
+}
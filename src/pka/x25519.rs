@@ -0,0 +1,124 @@
+//! X25519 key agreement (RFC 7748).
+//!
+//! X25519 works on u coordinates only, and u may belong to the quadratic
+//! twist of Curve25519 rather than to the curve itself. Neither case needs a
+//! square root here: with x = u + A / 3 on [`WEI25519`] and
+//! f = x<sup>3</sup> + ax + b, the point (f x, f<sup>2</sup>) lies on
+//! y<sup>2</sup> = x<sup>3</sup> + a f<sup>2</sup> x + b f<sup>3</sup>, a
+//! curve isomorphic to Wei25519 when f is a square and to its twist
+//! otherwise. The PKA multiplies on that curve and the x coordinate of the
+//! result is divided by f again.
+
+use zeroize::Zeroize;
+
+use super::{
+    curve::{WEI25519, WEI25519_U_OFFSET},
+    AffinePoint, EccMulMode, EllipticCurve, Error, Pka, ProjectivePoint, Sign,
+};
+use crate::uint::Uint;
+
+/// The u coordinate of the Curve25519 base point, 9.
+pub const X25519_BASEPOINT: [u8; 32] = {
+    let mut u = [0; 32];
+    u[0] = 9;
+    u
+};
+
+/// Field modulus p = 2<sup>255</sup> - 19.
+const P: Uint<8> = WEI25519.modulus;
+/// (p - 1) / 2, the exponent of the quadratic residue test.
+const LEGENDRE_EXPONENT: Uint<8> =
+    Uint::from_be_hex("3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff6");
+/// Order of the prime subgroup of the twist, whose order is 4 times it.
+const TWIST_PRIME_ORDER: Uint<8> =
+    Uint::from_be_hex("1fffffffffffffffffffffffffffffffd6420c42ba10c6534fdb39cb4614581d");
+
+/// Computes X25519(`scalar`, `u`): the u coordinate of `scalar` times the
+/// point with u coordinate `u`, both little-endian.
+///
+/// The scalar is clamped and the top bit of `u` is ignored, as RFC 7748
+/// requires; u values not smaller than p are reduced. The multiplication
+/// runs in [`EccMulMode::Protected`]. Points of small order give the
+/// all-zero output, which callers may want to reject (RFC 7748, section 6.1).
+pub fn x25519(pka: &mut Pka, scalar: &[u8; 32], u: &[u8; 32]) -> Result<[u8; 32], Error> {
+    let mut clamped = *scalar;
+    clamped[0] &= 248;
+    clamped[31] &= 127;
+    clamped[31] |= 64;
+    // The scalar is a multiple of the cofactor 8: multiply 8 * P by scalar / 8,
+    // which is smaller than both prime orders.
+    let mut k = Uint::<8>::from_le_words(le_words(&clamped)).shr(3);
+    clamped.zeroize();
+
+    let result = ladder(pka, &k, u);
+    k.zeroize();
+    result
+}
+
+fn ladder(pka: &mut Pka, k: &Uint<8>, u: &[u8; 32]) -> Result<[u8; 32], Error> {
+    let mut u_bytes = *u;
+    u_bytes[31] &= 0x7f;
+    let mut u = Uint::<8>::from_le_words(le_words(&u_bytes));
+    if u >= P {
+        u = u.sbb(&P).0;
+    }
+
+    // f = x^3 + ax + b for x = u + A / 3; f = 0 only for u = 0, of order 2.
+    let x = pka.mod_add(&u, &WEI25519_U_OFFSET, &P)?;
+    let x2 = pka.mod_mul(&x, &x, &P)?;
+    let x2_plus_a = pka.mod_add(&x2, &WEI25519.coef_a, &P)?;
+    let x3_plus_ax = pka.mod_mul(&x2_plus_a, &x, &P)?;
+    let f = pka.mod_add(&x3_plus_ax, &WEI25519.coef_b, &P)?;
+    if f.is_zero() {
+        return Ok([0; 32]);
+    }
+
+    let f2 = pka.mod_mul(&f, &f, &P)?;
+    let f3 = pka.mod_mul(&f2, &f, &P)?;
+    let is_square = pka.mod_exp(&f, &LEGENDRE_EXPONENT, &P)? == Uint::ONE;
+    let curve = EllipticCurve {
+        coef_sign: Sign::Pos,
+        coef_a: pka.mod_mul(&WEI25519.coef_a, &f2, &P)?,
+        coef_b: pka.mod_mul(&WEI25519.coef_b, &f3, &P)?,
+        modulus: P,
+        base_point_x: pka.mod_mul(&f, &x, &P)?,
+        base_point_y: f2,
+        prime_order: if is_square {
+            WEI25519.prime_order
+        } else {
+            TWIST_PRIME_ORDER
+        },
+    };
+
+    // Clear the small order component
+    let mut point = ProjectivePoint::from_affine(&AffinePoint::generator(&curve));
+    for _ in 0..3 {
+        point = pka.ecc_double(&curve, &point)?;
+    }
+    let point = match pka.ecc_to_affine(&curve, &point) {
+        Ok(point) => point,
+        Err(Error::PointAtInfinity) => return Ok([0; 32]),
+        Err(e) => return Err(e),
+    };
+
+    let product = pka.ecc_mul(&curve, k, &point, EccMulMode::Protected)?;
+    let f_inv = pka.mod_inv(&f, &P)?;
+    let x = pka.mod_mul(&product.x, &f_inv, &P)?;
+    let u = pka.mod_sub(&x, &WEI25519_U_OFFSET, &P)?;
+
+    let mut out = [0; 32];
+    u.as_le_words()
+        .iter()
+        .zip(out.chunks_exact_mut(4))
+        .for_each(|(word, bytes)| bytes.copy_from_slice(&word.to_le_bytes()));
+    Ok(out)
+}
+
+fn le_words(bytes: &[u8; 32]) -> [u32; 8] {
+    let mut words = [0; 8];
+    words
+        .iter_mut()
+        .zip(bytes.chunks_exact(4))
+        .for_each(|(word, chunk)| *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
+    words
+}
@@ -0,0 +1,64 @@
+//! True random number generator (RNG) driver.
+//!
+//! The RNG clock and configuration are set up by [`Pka::new`]; this driver
+//! only reads `RNG_DR`.
+//!
+//! [`Pka::new`]: crate::pka::Pka::new
+
+use core::num::NonZeroU32;
+
+use stm32wba::stm32wba55;
+use cortex_m::asm;
+use rand_core::{CryptoRng, RngCore};
+
+/// Error code reported when the RNG flags a seed error (`SECS`).
+const SEED_ERROR: u32 = rand_core::Error::CUSTOM_START;
+
+/// RNG driver.
+#[derive(Debug)]
+pub struct Rng<'a> {
+    rng: &'a stm32wba55::RNG,
+}
+
+impl<'a> Rng<'a> {
+    pub fn new(rng: &'a stm32wba55::RNG) -> Self {
+        Self { rng }
+    }
+
+    fn try_next_u32(&mut self) -> Result<u32, rand_core::Error> {
+        loop {
+            let sr = self.rng.rng_sr().read();
+            if sr.secs().bit_is_set() {
+                return Err(NonZeroU32::new(SEED_ERROR).unwrap().into());
+            }
+            if sr.drdy().bit_is_set() {
+                return Ok(self.rng.rng_dr().read().bits());
+            }
+            asm::nop();
+        }
+    }
+}
+
+impl RngCore for Rng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        self.try_next_u32().expect("RNG seed error")
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        rand_core::impls::next_u64_via_u32(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).expect("RNG seed error")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        for chunk in dest.chunks_mut(4) {
+            let word = self.try_next_u32()?.to_le_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
+impl CryptoRng for Rng<'_> {}
@@ -0,0 +1,454 @@
+//! Fixed-width unsigned integers used as PKA operands.
+//!
+//! The PKA RAM stores every operand as little-endian 32-bit words (least
+//! significant word at the lowest address), while curve parameters and keys
+//! are usually written down as big-endian byte strings. [`Uint`] keeps its
+//! limbs in the PKA order so the driver can copy them straight into RAM, and
+//! offers the byte and hex conversions callers need.
+
+use core::{cmp::Ordering, fmt};
+
+use rand_core::{CryptoRng, RngCore};
+use zeroize::Zeroize;
+
+/// Errors from parsing or encoding a [`Uint`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// Input or output buffer does not fit the integer width.
+    Length,
+    /// Input contains a character that is not a hexadecimal digit.
+    Hex,
+}
+
+/// Unsigned integer of `LIMBS` 32-bit words.
+///
+/// Limbs are stored least significant first, which is the word order the PKA
+/// expects in its RAM.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Uint<const LIMBS: usize> {
+    limbs: [u32; LIMBS],
+}
+
+impl<const LIMBS: usize> Uint<LIMBS> {
+    /// The value 0.
+    pub const ZERO: Self = Self { limbs: [0; LIMBS] };
+    /// The value 1.
+    pub const ONE: Self = Self::from_u32(1);
+    /// Width of the integer in bits.
+    pub const BITS: usize = LIMBS * 32;
+    /// Width of the integer in bytes.
+    pub const BYTES: usize = LIMBS * 4;
+
+    /// Creates an integer from a single word.
+    pub const fn from_u32(value: u32) -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = value;
+        Self { limbs }
+    }
+
+    /// Creates an integer from little-endian words (least significant first).
+    pub const fn from_le_words(words: [u32; LIMBS]) -> Self {
+        Self { limbs: words }
+    }
+
+    /// Creates an integer from big-endian words (most significant first).
+    ///
+    /// This is the order used when writing constants by hand, e.g.
+    /// `[0xffffffff, 0x00000001, ...]` for the P-256 modulus.
+    pub const fn from_be_words(words: [u32; LIMBS]) -> Self {
+        let mut limbs = [0; LIMBS];
+        let mut i = 0;
+        while i < LIMBS {
+            limbs[i] = words[LIMBS - 1 - i];
+            i += 1;
+        }
+        Self { limbs }
+    }
+
+    /// Returns the words least significant first, as laid out in PKA RAM.
+    pub const fn as_le_words(&self) -> &[u32; LIMBS] {
+        &self.limbs
+    }
+
+    /// Returns the words most significant first.
+    pub const fn to_be_words(&self) -> [u32; LIMBS] {
+        let mut words = [0; LIMBS];
+        let mut i = 0;
+        while i < LIMBS {
+            words[i] = self.limbs[LIMBS - 1 - i];
+            i += 1;
+        }
+        words
+    }
+
+    pub(crate) fn as_le_words_mut(&mut self) -> &mut [u32; LIMBS] {
+        &mut self.limbs
+    }
+
+    /// Decodes a big-endian byte string.
+    ///
+    /// Inputs shorter than [`Self::BYTES`] are zero-extended, so a 66-byte
+    /// P-521 coordinate can be decoded into a 17-word integer.
+    pub fn from_be_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() > Self::BYTES {
+            return Err(Error::Length);
+        }
+        let mut limbs = [0; LIMBS];
+        for (idx, &byte) in bytes.iter().rev().enumerate() {
+            limbs[idx / 4] |= (byte as u32) << (8 * (idx % 4));
+        }
+        Ok(Self { limbs })
+    }
+
+    /// Decodes a little-endian byte string.
+    ///
+    /// Inputs shorter than [`Self::BYTES`] are zero-extended.
+    pub fn from_le_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() > Self::BYTES {
+            return Err(Error::Length);
+        }
+        let mut limbs = [0; LIMBS];
+        for (idx, &byte) in bytes.iter().enumerate() {
+            limbs[idx / 4] |= (byte as u32) << (8 * (idx % 4));
+        }
+        Ok(Self { limbs })
+    }
+
+    /// Encodes the integer big-endian into `out`, left-padded with zeros.
+    ///
+    /// `out` may be shorter than [`Self::BYTES`] as long as the value fits,
+    /// otherwise [`Error::Length`] is returned and `out` is left untouched.
+    pub fn to_be_bytes(&self, out: &mut [u8]) -> Result<(), Error> {
+        if self.bits() > out.len() * 8 {
+            return Err(Error::Length);
+        }
+        let len = out.len();
+        for (idx, byte) in out.iter_mut().enumerate() {
+            *byte = self.byte(len - 1 - idx);
+        }
+        Ok(())
+    }
+
+    /// Encodes the integer little-endian into `out`, right-padded with zeros.
+    ///
+    /// Same length rules as [`Self::to_be_bytes`].
+    pub fn to_le_bytes(&self, out: &mut [u8]) -> Result<(), Error> {
+        if self.bits() > out.len() * 8 {
+            return Err(Error::Length);
+        }
+        for (idx, byte) in out.iter_mut().enumerate() {
+            *byte = self.byte(idx);
+        }
+        Ok(())
+    }
+
+    /// Parses a big-endian hexadecimal string, panicking on invalid input.
+    ///
+    /// Intended for constants:
+    ///
+    /// ```ignore
+    /// const N: Uint<8> = Uint::from_be_hex("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551");
+    /// ```
+    pub const fn from_be_hex(hex: &str) -> Self {
+        match Self::parse_be_hex(hex) {
+            Ok(value) => value,
+            Err(Error::Length) => panic!("hex string too long"),
+            Err(Error::Hex) => panic!("invalid hex digit"),
+        }
+    }
+
+    /// Parses a big-endian hexadecimal string.
+    ///
+    /// Upper and lower case digits are accepted; there is no `0x` prefix.
+    /// Strings shorter than `2 * Self::BYTES` digits are zero-extended.
+    pub const fn parse_be_hex(hex: &str) -> Result<Self, Error> {
+        let digits = hex.as_bytes();
+        if digits.len() > LIMBS * 8 {
+            return Err(Error::Length);
+        }
+        let mut limbs = [0; LIMBS];
+        let mut idx = 0;
+        while idx < digits.len() {
+            let nibble = match digits[digits.len() - 1 - idx] {
+                c @ b'0'..=b'9' => c - b'0',
+                c @ b'a'..=b'f' => c - b'a' + 10,
+                c @ b'A'..=b'F' => c - b'A' + 10,
+                _ => return Err(Error::Hex),
+            };
+            limbs[idx / 8] |= (nibble as u32) << (4 * (idx % 8));
+            idx += 1;
+        }
+        Ok(Self { limbs })
+    }
+
+    /// Returns `true` if the value is 0.
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().fold(0, |acc, &limb| acc | limb) == 0
+    }
+
+    /// Returns bit `idx` (0 is the least significant bit).
+    pub fn bit(&self, idx: usize) -> bool {
+        idx < Self::BITS && (self.limbs[idx / 32] >> (idx % 32)) & 1 == 1
+    }
+
+    /// Number of significant bits, 0 for the value 0.
+    pub fn bits(&self) -> usize {
+        self.limbs
+            .iter()
+            .rposition(|&limb| limb != 0)
+            .map_or(0, |idx| idx * 32 + 32 - self.limbs[idx].leading_zeros() as usize)
+    }
+
+    /// Converts to a different width, failing if the value does not fit.
+    pub fn resize<const OTHER: usize>(&self) -> Result<Uint<OTHER>, Error> {
+        if self.bits() > OTHER * 32 {
+            return Err(Error::Length);
+        }
+        let mut limbs = [0; OTHER];
+        limbs
+            .iter_mut()
+            .zip(self.limbs.iter())
+            .for_each(|(dst, &src)| *dst = src);
+        Ok(Uint { limbs })
+    }
+
+    /// Samples a uniformly random value in [1, `bound` - 1].
+    ///
+    /// Candidates are truncated to the bit length of `bound` and rejected
+    /// until one falls in range, so fewer than two draws are needed on average.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bound` is 0 or 1: the range is empty.
+    pub fn random_nonzero<R: RngCore + CryptoRng>(rng: &mut R, bound: &Self) -> Self {
+        assert!(*bound > Self::ONE, "random_nonzero: bound must be at least 2");
+        let bits = bound.bits();
+        loop {
+            let mut candidate = Self::ZERO;
+            for (idx, limb) in candidate.limbs.iter_mut().enumerate() {
+                let remaining = bits.saturating_sub(idx * 32);
+                *limb = match remaining {
+                    0 => 0,
+                    1..=31 => rng.next_u32() >> (32 - remaining),
+                    _ => rng.next_u32(),
+                };
+            }
+            if !candidate.is_zero() && candidate < *bound {
+                return candidate;
+            }
+            candidate.zeroize();
+        }
+    }
+
+    /// Swaps `self` and `other` if `choice` is set, without branching on it.
+    pub fn conditional_swap(&mut self, other: &mut Self, choice: bool) {
+        let mask = 0u32.wrapping_sub(core::hint::black_box(choice as u32));
+        self.limbs
+            .iter_mut()
+            .zip(other.limbs.iter_mut())
+            .for_each(|(a, b)| {
+                let t = (*a ^ *b) & mask;
+                *a ^= t;
+                *b ^= t;
+            });
+    }
+
+    /// Returns `self + rhs` if `choice` is set and `self` otherwise, without
+    /// branching on it. The carry out is dropped.
+    pub fn conditional_add(&self, rhs: &Self, choice: bool) -> Self {
+        let mask = 0u32.wrapping_sub(core::hint::black_box(choice as u32));
+        let mut masked = *rhs;
+        masked.limbs.iter_mut().for_each(|limb| *limb &= mask);
+        let sum = self.adc(&masked).0;
+        masked.zeroize();
+        sum
+    }
+
+    /// Computes `self + rhs`, returning the sum and the carry out (0 or 1).
+    pub fn adc(&self, rhs: &Self) -> (Self, u32) {
+        let mut limbs = [0; LIMBS];
+        let mut carry = 0u64;
+        for (idx, limb) in limbs.iter_mut().enumerate() {
+            let sum = self.limbs[idx] as u64 + rhs.limbs[idx] as u64 + carry;
+            *limb = sum as u32;
+            carry = sum >> 32;
+        }
+        (Self { limbs }, carry as u32)
+    }
+
+    /// Computes `self - rhs`, returning the difference and the borrow out (0 or 1).
+    pub fn sbb(&self, rhs: &Self) -> (Self, u32) {
+        let mut limbs = [0; LIMBS];
+        let mut borrow = 0u64;
+        for (idx, limb) in limbs.iter_mut().enumerate() {
+            let diff = (self.limbs[idx] as u64)
+                .wrapping_sub(rhs.limbs[idx] as u64)
+                .wrapping_sub(borrow);
+            *limb = diff as u32;
+            borrow = diff >> 63;
+        }
+        (Self { limbs }, borrow as u32)
+    }
+
+    /// Computes `self + rhs mod p`, for `self, rhs < p`.
+    ///
+    /// This and the other modular helpers are variable-time software
+    /// implementations, meant for reference computations and parameter
+    /// validation rather than for secret data.
+    pub fn add_mod(&self, rhs: &Self, p: &Self) -> Self {
+        let (sum, carry) = self.adc(rhs);
+        if carry == 1 || sum >= *p {
+            sum.sbb(p).0
+        } else {
+            sum
+        }
+    }
+
+    /// Computes `self - rhs mod p`, for `self, rhs < p`.
+    pub fn sub_mod(&self, rhs: &Self, p: &Self) -> Self {
+        let (diff, borrow) = self.sbb(rhs);
+        if borrow == 1 {
+            diff.adc(p).0
+        } else {
+            diff
+        }
+    }
+
+    /// Computes `-self mod p`, for `self < p`.
+    pub fn neg_mod(&self, p: &Self) -> Self {
+        Self::ZERO.sub_mod(self, p)
+    }
+
+    /// Computes `self * rhs mod p`, for `self, rhs < p`.
+    pub fn mul_mod(&self, rhs: &Self, p: &Self) -> Self {
+        (0..rhs.bits()).rev().fold(Self::ZERO, |acc, idx| {
+            let acc = acc.add_mod(&acc, p);
+            if rhs.bit(idx) {
+                acc.add_mod(self, p)
+            } else {
+                acc
+            }
+        })
+    }
+
+    /// Computes `self^exp mod p`, for `self < p`.
+    pub fn pow_mod<const EXP_LIMBS: usize>(&self, exp: &Uint<EXP_LIMBS>, p: &Self) -> Self {
+        (0..exp.bits()).rev().fold(Self::ONE, |acc, idx| {
+            let acc = acc.mul_mod(&acc, p);
+            if exp.bit(idx) {
+                acc.mul_mod(self, p)
+            } else {
+                acc
+            }
+        })
+    }
+
+    /// Computes `self^-1 mod p` for a prime `p`, or 0 if `self` is 0.
+    pub fn inv_mod(&self, p: &Self) -> Self {
+        self.pow_mod(&p.sbb(&Self::from_u32(2)).0, p)
+    }
+
+    /// Reduces `self` modulo `p` by repeated subtraction of shifted `p`.
+    pub fn rem(&self, p: &Self) -> Self {
+        let mut rem = *self;
+        let p_bits = p.bits();
+        let mut shift = self.bits().saturating_sub(p_bits);
+        loop {
+            let shifted = p.shl(shift);
+            if shifted.bits() == p_bits + shift && rem >= shifted {
+                rem = rem.sbb(&shifted).0;
+            }
+            if shift == 0 {
+                return rem;
+            }
+            shift -= 1;
+        }
+    }
+
+    /// Shifts left by `shift` bits, discarding bits shifted out.
+    pub fn shl(&self, shift: usize) -> Self {
+        let mut limbs = [0; LIMBS];
+        let (words, bits) = (shift / 32, shift % 32);
+        for idx in (words..LIMBS).rev() {
+            let lo = self.limbs[idx - words];
+            let carry = if bits > 0 && idx > words {
+                self.limbs[idx - words - 1] >> (32 - bits)
+            } else {
+                0
+            };
+            limbs[idx] = (lo << bits) | carry;
+        }
+        Self { limbs }
+    }
+
+    /// Shifts right by `shift` bits.
+    pub fn shr(&self, shift: usize) -> Self {
+        let mut limbs = [0; LIMBS];
+        let (words, bits) = (shift / 32, shift % 32);
+        for (idx, limb) in limbs.iter_mut().take(LIMBS.saturating_sub(words)).enumerate() {
+            let hi = self.limbs[idx + words];
+            let carry = if bits > 0 && idx + words + 1 < LIMBS {
+                self.limbs[idx + words + 1] << (32 - bits)
+            } else {
+                0
+            };
+            *limb = (hi >> bits) | carry;
+        }
+        Self { limbs }
+    }
+
+    fn byte(&self, idx: usize) -> u8 {
+        if idx < Self::BYTES {
+            (self.limbs[idx / 4] >> (8 * (idx % 4))) as u8
+        } else {
+            0
+        }
+    }
+}
+
+impl<const LIMBS: usize> Zeroize for Uint<LIMBS> {
+    fn zeroize(&mut self) {
+        self.limbs.zeroize();
+    }
+}
+
+impl<const LIMBS: usize> Default for Uint<LIMBS> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const LIMBS: usize> Ord for Uint<LIMBS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.iter().rev().cmp(other.limbs.iter().rev())
+    }
+}
+
+impl<const LIMBS: usize> PartialOrd for Uint<LIMBS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const LIMBS: usize> fmt::Debug for Uint<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Uint(0x")?;
+        self.limbs
+            .iter()
+            .rev()
+            .try_for_each(|limb| write!(f, "{:08X}", limb))?;
+        write!(f, ")")
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const LIMBS: usize> defmt::Format for Uint<LIMBS> {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(fmt, "0x");
+        self.limbs
+            .iter()
+            .rev()
+            .for_each(|limb| defmt::write!(fmt, "{=u32:08X}", limb));
+    }
+}